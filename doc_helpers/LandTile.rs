@@ -0,0 +1,40 @@
+# use std::collections::HashSet;
+# use procedural::{Board, MaybeTile, Tile};
+# use procedural::directions::Direction4;
+# #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+# enum Land {
+#     Grass,
+#     Water
+# }
+# // Any tile can be next to any other
+# impl Tile for Land {
+#     type Direction = Direction4;
+#     fn all() -> HashSet<Self> {
+#         HashSet::from([Land::Grass, Land::Water])
+#     }
+#     fn possibles(_layer: usize) -> HashSet<Self> {
+#         Self::all()
+#     }
+#     fn get_rules(&self) -> Box<dyn Fn(&Self, Direction4) -> bool + '_> {
+#         Box::new(|_: &Land, _: Direction4| true)
+#     }
+#     fn get_distribution(&self, _layer: usize) -> u32 {
+#         1
+#     }
+# }
+# // The (row, col) of the tiles like the one at `start` that can be reached from it
+# fn flood(board: &Board<Land>, start: (usize, usize)) -> Vec<(usize, usize)> {
+#     let tile = board.get_tile(start.0, start.1, 0).unwrap();
+#     let mut group = vec![start];
+#     let mut i = 0;
+#     while i < group.len() {
+#         let (row, col) = group[i];
+#         for next in [(row.wrapping_sub(1), col), (row + 1, col), (row, col.wrapping_sub(1)), (row, col + 1)] {
+#             if board.get_tile(next.0, next.1, 0).is_ok_and(|next_tile| next_tile == tile) && !group.contains(&next) {
+#                 group.push(next)
+#             }
+#         }
+#         i += 1;
+#     }
+#     group
+# }
@@ -7,6 +7,7 @@
 #     fn opposite(&self) -> Self {self.clone()}
 # }
 # #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+# #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 # enum MyTile {
 #    Yellow,
 #    Green,
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+use procedural::{Board, MaybeTile};
+use procedural::directions::Direction4;
+use procedural::sockets::{SocketTile, Socketed};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RoadTile {
+    Grass,
+    RoadNorthSouth,
+    RoadEastWest,
+    Crossing
+}
+
+#[derive(PartialEq)]
+enum Side {
+    Grass,
+    Road
+}
+
+impl SocketTile for RoadTile {
+    type Direction = Direction4;
+    type Socket = Side;
+
+    fn all() -> HashSet<Self> {
+        HashSet::from([RoadTile::Grass, RoadTile::RoadNorthSouth, RoadTile::RoadEastWest, RoadTile::Crossing])
+    }
+
+    fn possibles(_layer: usize) -> HashSet<Self> {
+        Self::all()
+    }
+
+    fn socket(&self, direction: Direction4) -> Side {
+        match (self, direction) {
+            (RoadTile::Grass, _) => Side::Grass,
+            (RoadTile::RoadNorthSouth, Direction4::North | Direction4::South) => Side::Road,
+            (RoadTile::RoadEastWest, Direction4::East | Direction4::West) => Side::Road,
+            (RoadTile::Crossing, _) => Side::Road,
+            _ => Side::Grass,
+        }
+    }
+
+    fn get_distribution(&self, _layer: usize) -> u32 {
+        1
+    }
+}
+
+let mut board = Board::<Socketed<RoadTile>>::new(8, 8, 1);
+board.generate().unwrap();
+// Every road continues in the next tile
+for row in 0..8 {
+    for col in 0..7 {
+        let (MaybeTile::Decided(Socketed(west)), MaybeTile::Decided(Socketed(east))) = (board.get_tile(row, col, 0).unwrap(), board.get_tile(row, col + 1, 0).unwrap()) else {
+            unreachable!()
+        };
+        assert!(west.socket(Direction4::East) == east.socket(Direction4::West));
+    }
+}
@@ -0,0 +1,64 @@
+# use std::collections::HashSet;
+# use procedural::{Board, CoordError, Direction, MaybeTile, Tile};
+# use procedural::graph::Graph;
+# use procedural::selection::Scanline;
+# #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+# enum Link {
+#     Wall,
+#     Door
+# }
+# impl Direction for Link {
+#     fn all() -> Vec<Self> {
+#         vec![Link::Wall, Link::Door]
+#     }
+#     fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, height: u32) -> Result<(usize, usize, usize), CoordError> {
+#         Err(CoordError)
+#     }
+#     fn opposite(&self) -> Self {
+#         *self
+#     }
+# }
+# #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+# enum Color {
+#     Red,
+#     Green,
+#     Blue
+# }
+# // Rooms with a wall between them have different colors, and red and blue rooms don't share a door
+# impl Tile for Color {
+#     type Direction = Link;
+#     fn all() -> HashSet<Self> {
+#         HashSet::from([Color::Red, Color::Green, Color::Blue])
+#     }
+#     fn possibles(_layer: usize) -> HashSet<Self> {
+#         Self::all()
+#     }
+#     fn get_rules(&self) -> Box<dyn Fn(&Self, Link) -> bool + '_> {
+#         Box::new(move |other: &Color, link: Link| match link {
+#             Link::Wall => self != other,
+#             Link::Door => !matches!((self, other), (Color::Red, Color::Blue) | (Color::Blue, Color::Red)),
+#         })
+#     }
+#     fn get_distribution(&self, _layer: usize) -> u32 {
+#         1
+#     }
+# }
+# // Rooms 2, 3 and 4 share walls, and all of them have a door to room 0. Room 1 is on its own.
+# // The rooms are decided in order, and painted red whenever possible,
+# // but a red room 0 leaves only two colors for the three rooms with walls
+# fn rooms() -> Board<Color> {
+#     let mut graph = Graph::new(5);
+#     for (a, b) in [(2, 3), (3, 4), (4, 2)] {
+#         graph.connect(a, b, Link::Wall);
+#     }
+#     for room in 2..5 {
+#         graph.connect(0, room, Link::Door);
+#     }
+#     let mut board = Board::<Color>::from_graph(&graph);
+#     board.set_cell_selector(Scanline);
+#     board.set_tile_selector(|_board: &Board<Color>, tile: Color, _row: usize, _col: usize, _layer: usize| match tile {
+#         Color::Red => 1,
+#         _ => 0
+#     });
+#     board
+# }
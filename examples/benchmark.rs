@@ -0,0 +1,56 @@
+//! Times how long generating boards of different sizes takes.
+//!
+//! Run with `cargo run --release --example benchmark`
+use std::collections::HashSet;
+use std::time::Instant;
+
+use procedural::{Board, Tile};
+use procedural::directions::Direction6;
+
+/// 4 heights of terrain, where neighbouring slots can't differ by more than 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Terrain(u8);
+
+impl Tile for Terrain {
+    type Direction = Direction6;
+
+    fn all() -> HashSet<Self> {
+        (0..4).map(Terrain).collect()
+    }
+
+    fn possibles(_layer: usize) -> HashSet<Self> {
+        Self::all()
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_name(&self) -> String {
+        format!("terrain{}", self.0)
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_model(&self) -> Option<(Vec<te_renderer::model::ModelVertex>, Vec<u32>, String)> {
+        None
+    }
+
+    #[cfg(feature = "view3d")]
+    fn has_model(&self) -> bool {
+        false
+    }
+
+    fn get_rules(&self) -> Box<dyn Fn(&Self, Direction6) -> bool + '_> {
+        Box::new(move |other: &Terrain, _: Direction6| self.0.abs_diff(other.0) <= 1)
+    }
+
+    fn get_distribution(&self, _layer: usize) -> u32 {
+        1
+    }
+}
+
+fn main() {
+    for (width, length, height) in [(50, 50, 1), (100, 100, 1), (200, 200, 1), (256, 256, 4)] {
+        let mut board = Board::<Terrain>::with_seed(width, length, height, 42);
+        let start = Instant::now();
+        board.generate().unwrap();
+        println!("{width}x{length}x{height}: {:.2?}", start.elapsed());
+    }
+}
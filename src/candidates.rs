@@ -0,0 +1,118 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::splitmix64;
+
+/// The undecided slots of the board, ordered by layer and by the score of the [CellSelector](crate::CellSelector),
+/// so the next slot to decide is found without going through the whole layer.
+///
+/// Every slot is indexed by its position in the board, layer by layer and row by row.
+/// A slot is pushed again every time it changes, and its older entries are skipped when they reach the top.
+pub(crate) struct Candidates {
+    heap: BinaryHeap<Reverse<Candidate>>,
+    /// How many times every slot has changed. Entries with an older version are outdated
+    versions: Vec<u32>,
+    /// How many undecided slots every layer has
+    undecided: Vec<usize>,
+    /// Mixed with the index of every slot to break ties between slots with the same score
+    tiebreak_seed: u64
+}
+
+impl Candidates {
+    pub(crate) fn new(slots: usize, layers: usize, tiebreak_seed: u64) -> Candidates {
+        Candidates {
+            heap: BinaryHeap::new(),
+            versions: vec![0; slots],
+            undecided: vec![0; layers],
+            tiebreak_seed
+        }
+    }
+
+    /// Updates the slot at `index` after it changed. `undecided` is its score and if it can't have any tile,
+    /// or None if it's decided now
+    pub(crate) fn update(&mut self, index: usize, layer: usize, was_undecided: bool, undecided: Option<(f64, bool)>) {
+        self.versions[index] = self.versions[index].wrapping_add(1);
+        match (was_undecided, undecided.is_some()) {
+            (true, false) => self.undecided[layer] -= 1,
+            (false, true) => self.undecided[layer] += 1,
+            _ => (),
+        }
+        if let Some((score, empty)) = undecided {
+            self.heap.push(Reverse(Candidate {
+                layer,
+                possible: !empty,
+                no_score: score.is_nan(),
+                score,
+                tiebreak: splitmix64(index as u64 ^ self.tiebreak_seed),
+                index,
+                version: self.versions[index]
+            }));
+            if self.heap.len() > 2 * self.versions.len() + 64 {
+                self.compact()
+            }
+        }
+    }
+
+    /// How many undecided slots `layer` has
+    pub(crate) fn undecided(&self, layer: usize) -> usize {
+        self.undecided[layer]
+    }
+
+    /// The index and layer of the undecided slot with the lowest layer and score, and if it can't have any tile.
+    /// Slots that can't have any tile come first in their layer.
+    pub(crate) fn first(&mut self) -> Option<(usize, usize, bool)> {
+        while let Some(Reverse(candidate)) = self.heap.peek() {
+            if candidate.version == self.versions[candidate.index] {
+                return Some((candidate.index, candidate.layer, !candidate.possible))
+            }
+            self.heap.pop();
+        }
+        None
+    }
+
+    /// Removes the outdated entries
+    fn compact(&mut self) {
+        let heap = std::mem::take(&mut self.heap).into_vec();
+        self.heap = heap.into_iter()
+            .filter(|Reverse(candidate)| candidate.version == self.versions[candidate.index])
+            .collect();
+    }
+}
+
+struct Candidate {
+    layer: usize,
+    /// Slots that can't have any tile are contradictions, so they come first
+    possible: bool,
+    /// Slots with a NaN score come after the ones with a score
+    no_score: bool,
+    score: f64,
+    tiebreak: u64,
+    index: usize,
+    version: u32
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.layer.cmp(&other.layer)
+            .then(self.possible.cmp(&other.possible))
+            .then(self.no_score.cmp(&other.no_score))
+            .then(self.score.total_cmp(&other.score))
+            .then(self.tiebreak.cmp(&other.tiebreak))
+            .then(self.index.cmp(&other.index))
+            .then(self.version.cmp(&other.version))
+    }
+}
@@ -0,0 +1,204 @@
+//! Endless worlds generated one chunk at a time. See [ChunkManager]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use crate::{BadPlacementError, Board, Direction, ImpossibleBoardError, MaybeTile, Slot, Tile, splitmix64};
+use crate::tile_set::TileSet;
+
+/// Generates an endless world as a grid of [Board]s of the same size, called chunks, only when they are needed.
+///
+/// Every new chunk takes into account the chunks around it that have already been generated,
+/// so the tiles at both sides of the seams always follow the rules.
+/// Chunk (0, 0) covers rows 0 to length-1 and columns 0 to width-1 of the world, chunk (0, 1) the next width columns, and so on.
+///
+/// The neighbours of a slot are found by calling [Direction::neighbour()] as if the chunk was in the middle of a board of 3x3 chunks,
+/// so directions that wrap around the edges of the board are not supported.
+/// Hexagonal directions need chunks with an even length.
+/// ```
+#[doc = include_str!("../doc_helpers/MyTile.rs")]
+/// use procedural::chunks::ChunkManager;
+/// let mut world = ChunkManager::<MyTile>::new(16, 16, 1, 42);
+/// // The player is at chunk (0, 0), generate everything around it
+/// for row in -1..=1 {
+///     for col in -1..=1 {
+///         world.chunk(row, col).unwrap();
+///     }
+/// }
+/// assert!(world.get_tile(-5, 20, 0).is_some());
+/// // The player moved away, forget the chunks that are far from it
+/// world.retain(|row, col| col >= 0);
+/// assert!(world.get(0, -1).is_none());
+/// ```
+pub struct ChunkManager<T>
+where
+    T: Tile
+{
+    width: u32,
+    length: u32,
+    height: u32,
+    seed: u64,
+    chunks: HashMap<(i64, i64), Board<T>>
+}
+
+impl<T> ChunkManager<T>
+where
+    T: Tile
+{
+    /// A world made of chunks of the given size, where chunks are generated from `seed` and their position
+    pub fn new(width: u32, length: u32, height: u32, seed: u64) -> ChunkManager<T> {
+        ChunkManager {
+            width,
+            length,
+            height,
+            seed,
+            chunks: HashMap::new()
+        }
+    }
+
+    /// Returns the chunk at the given position, generating it first if it's not cached.
+    ///
+    /// [ChunkError] if the chunks around it don't leave any possible way to generate it.
+    pub fn chunk(&mut self, row: i64, col: i64) -> Result<&Board<T>, ChunkError<T>> {
+        if !self.chunks.contains_key(&(row, col)) {
+            let chunk = self.generate_chunk(row, col)?;
+            self.chunks.insert((row, col), chunk);
+        }
+        Ok(&self.chunks[&(row, col)])
+    }
+
+    /// Returns the chunk at the given position if it's cached, without generating it
+    pub fn get(&self, row: i64, col: i64) -> Option<&Board<T>> {
+        self.chunks.get(&(row, col))
+    }
+
+    /// Returns the tile at a position of the world, if its chunk is cached
+    pub fn get_tile(&self, row: i64, col: i64, layer: usize) -> Option<T> {
+        let (length, width) = (self.length as i64, self.width as i64);
+        let chunk = self.get(row.div_euclid(length), col.div_euclid(width))?;
+        match chunk.get_tile(row.rem_euclid(length) as usize, col.rem_euclid(width) as usize, layer) {
+            Ok(MaybeTile::Decided(tile)) => Some(tile),
+            _ => None,
+        }
+    }
+
+    /// Positions of the cached chunks
+    pub fn chunks(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Adds a chunk to the cache, for example one that was evicted and saved before.
+    /// It will be used as is, without checking the seams with the chunks around it
+    pub fn insert(&mut self, row: i64, col: i64, chunk: Board<T>) {
+        self.chunks.insert((row, col), chunk);
+    }
+
+    /// Removes a chunk from the cache and returns it.
+    ///
+    /// If it's needed again, it will be generated again, and it may be different since the chunks around it may have changed.
+    pub fn evict(&mut self, row: i64, col: i64) -> Option<Board<T>> {
+        self.chunks.remove(&(row, col))
+    }
+
+    /// Keeps only the chunks whose position (row, col) meets the condition
+    pub fn retain(&mut self, mut keep: impl FnMut(i64, i64) -> bool) {
+        self.chunks.retain(|(row, col), _| keep(*row, *col))
+    }
+
+    fn generate_chunk(&self, row: i64, col: i64) -> Result<Board<T>, ChunkError<T>> {
+        let mut board = Board::<T>::with_seed(self.width, self.length, self.height, self.chunk_seed(row, col));
+        let (width, length) = (self.width as usize, self.length as usize);
+        let directions = board.rules.directions().to_vec();
+        for layer in 0..self.height as usize {
+            for slot_row in 0..length {
+                for slot_col in 0..width {
+                    let mut allowed: Option<TileSet> = None;
+                    for (d, direction) in directions.iter().enumerate() {
+                        let (neighbour_row, neighbour_col, neighbour_layer) = match direction.neighbour(
+                            slot_row + length,
+                            slot_col + width,
+                            layer,
+                            self.width * 3,
+                            self.length * 3,
+                            self.height
+                        ) {
+                            Ok(neighbour) => neighbour,
+                            Err(_) => continue,
+                        };
+                        let chunk = (row + (neighbour_row / length) as i64 - 1, col + (neighbour_col / width) as i64 - 1);
+                        if chunk == (row, col) {
+                            continue
+                        }
+                        let neighbour = match self.chunks.get(&chunk) {
+                            Some(neighbour) => neighbour,
+                            None => continue,
+                        };
+                        // Every chunk has the same rules, so tiles have the same index in all of them
+                        let tile = match neighbour.tiles[neighbour_layer][neighbour_row % length][neighbour_col % width] {
+                            Slot::Decided(tile) => tile,
+                            Slot::Undecided(_) => continue,
+                        };
+                        let allowed = allowed.get_or_insert_with(|| board.tiles[layer][slot_row][slot_col].iter().collect());
+                        *allowed = allowed.iter().filter(|candidate| board.rules.allows(*candidate, d, tile)).collect();
+                    }
+                    if let Some(allowed) = allowed {
+                        if allowed.len() == board.tiles[layer][slot_row][slot_col].len() {
+                            continue
+                        }
+                        let allowed = board.rules.to_hash_set(&allowed);
+                        let placed = match allowed.is_empty() {
+                            true => Err(BadPlacementError::ImpossibleTile),
+                            false => board.set_tile(MaybeTile::Undecided(allowed), slot_row, slot_col, layer),
+                        };
+                        if let Err(error) = placed {
+                            return Err(ChunkError::Seam { coord: (slot_row, slot_col, layer), error })
+                        }
+                    }
+                }
+            }
+        }
+        board.generate().map_err(ChunkError::Impossible)?;
+        Ok(board)
+    }
+
+    /// The seed of the chunk at the given position
+    fn chunk_seed(&self, row: i64, col: i64) -> u64 {
+        splitmix64(splitmix64(splitmix64(self.seed) ^ row as u64) ^ col as u64)
+    }
+}
+
+/// Returned when a chunk can't be generated. See [ChunkManager::chunk()]
+pub enum ChunkError<T>
+where
+    T: Tile
+{
+    /// The tiles of the chunks around it can't be next to any tile that can still be in a slot of its seams
+    Seam {
+        /// (row, col, layer) of the slot in the chunk
+        coord: (usize, usize, usize),
+        /// Why the tiles allowed by the chunks around it couldn't be placed there. See [Board::set_tile()]
+        error: BadPlacementError
+    },
+    /// The seams could be placed, but there is no way to generate the rest of the chunk
+    Impossible(ImpossibleBoardError<T>)
+}
+
+impl<T> Debug for ChunkError<T>
+where
+    T: Tile
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChunkError::Seam { coord, error } => f.debug_struct("Seam")
+                .field("coord", coord)
+                .field("error", error)
+                .finish(),
+            ChunkError::Impossible(error) => f.debug_tuple("Impossible").field(error).finish(),
+        }
+    }
+}
+
+impl<T> std::error::Error for ChunkError<T>
+where
+    T: Tile
+{}
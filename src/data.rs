@@ -0,0 +1,466 @@
+//! Tile sets defined in configuration files instead of code. See [DataTileSet]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::Arc;
+#[cfg(feature = "view3d")]
+use std::path::Path;
+
+use serde::Deserialize;
+#[cfg(feature = "view3d")]
+use te_renderer::model::ModelVertex;
+
+use crate::{Board, BoardTile, Direction, RuleSet};
+use crate::rules::Rules;
+use crate::tile_set::TileSet;
+
+/// The contents of a tile set file. See [DataTileSet]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileSetConfig {
+    /// Every tile of the set. The names must be different
+    pub tiles: Vec<TileConfig>
+}
+
+/// A tile of a [TileSetConfig]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileConfig {
+    /// Name of the tile, used by the other tiles to refer to it
+    pub name: String,
+    /// Layers where the tile can be placed. Every layer if missing
+    #[serde(default)]
+    pub layers: Option<Vec<usize>>,
+    /// How likely the tile is to be placed. See [Tile::get_distribution()](crate::Tile::get_distribution())
+    #[serde(default)]
+    pub weight: Weight,
+    /// For every direction (written like its `Debug` output, for example `"North"`), the tiles that can be there.
+    /// Directions that are missing don't allow any tile
+    #[serde(default)]
+    pub neighbours: HashMap<String, Vec<String>>,
+    /// Wavefront OBJ file in `resources/models` with the model of the tile, only used with the "view3d" feature
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Image in `resources/tiles` with the texture of the model, required if there is a model
+    #[serde(default)]
+    pub texture: Option<String>
+}
+
+/// How likely a tile of a [TileConfig] is to be placed
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Weight {
+    /// The same in every layer
+    Same(u32),
+    /// One weight for each layer, starting from layer 0. Layers past the end use the last one
+    PerLayer(Vec<u32>)
+}
+
+impl Default for Weight {
+    fn default() -> Self {
+        Weight::Same(1)
+    }
+}
+
+/// A mistake in a tile set file. See [DataTileSet]
+#[derive(Debug)]
+pub enum DataError {
+    /// The file is not valid for its format, or doesn't have the expected fields
+    Parse(String),
+    /// The file has no tiles
+    NoTiles,
+    /// More than one tile has this name
+    DuplicateTile(String),
+    /// A tile has neighbours in a direction that doesn't exist
+    UnknownDirection {
+        /// Name of the tile
+        tile: String,
+        /// The direction that doesn't exist
+        direction: String
+    },
+    /// A tile has a neighbour that doesn't exist
+    UnknownNeighbour {
+        /// Name of the tile
+        tile: String,
+        /// Direction of the neighbour
+        direction: String,
+        /// The neighbour that doesn't exist
+        neighbour: String
+    },
+    /// A tile allows a neighbour, but the neighbour doesn't allow the tile in the opposite direction
+    OneWayRule {
+        /// Name of the tile
+        tile: String,
+        /// Direction of the neighbour
+        direction: String,
+        /// Name of the neighbour
+        neighbour: String,
+        /// The opposite direction, where the neighbour should allow the tile
+        opposite: String
+    },
+    /// A tile has an empty list of weights
+    NoWeights(String),
+    /// A tile has a model but no texture
+    MissingTexture(String),
+    /// The model of a tile can't be loaded
+    BadModel {
+        /// Name of the tile
+        tile: String,
+        /// What went wrong
+        reason: String
+    }
+}
+
+impl std::error::Error for DataError {}
+
+/// What a [DataTile] needs to know about itself. Shared by all the tiles of a [DataTileSet]
+#[derive(Debug)]
+struct TileData {
+    name: String,
+    #[cfg(feature = "view3d")]
+    /// Position and texture coordinates of every vertex, the triangles and the texture
+    model: Option<(Vec<([f32; 3], [f32; 2])>, Vec<u32>, String)>
+}
+
+/// A tile of a [DataTileSet], whose rules are in `D` directions.
+///
+/// Its rules come from the file, so boards of data tiles can only be made with [DataTileSet::board()]
+/// ```compile_fail
+/// use procedural::Board;
+/// use procedural::data::DataTile;
+/// use procedural::directions::Direction4;
+/// let board = Board::<DataTile<Direction4>>::new(10, 10, 1);
+/// ```
+pub struct DataTile<D> {
+    /// The data of every tile of the set, so it's freed once the set and all its tiles are dropped
+    table: Arc<[TileData]>,
+    index: usize,
+    direction: PhantomData<D>
+}
+
+impl<D> DataTile<D> {
+    /// The name of the tile in the file
+    pub fn name(&self) -> &str {
+        &self.data().name
+    }
+
+    fn data(&self) -> &TileData {
+        &self.table[self.index]
+    }
+}
+
+impl<D> Clone for DataTile<D> {
+    fn clone(&self) -> Self {
+        DataTile {
+            table: Arc::clone(&self.table),
+            index: self.index,
+            direction: PhantomData
+        }
+    }
+}
+
+impl<D> PartialEq for DataTile<D> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.table, &other.table) && self.index == other.index
+    }
+}
+
+impl<D> Eq for DataTile<D> {}
+
+impl<D> Hash for DataTile<D> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // The name, and not the address, so the order of the tiles doesn't change between runs
+        self.data().name.hash(state)
+    }
+}
+
+impl<D> Debug for DataTile<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl<D> BoardTile for DataTile<D>
+where
+    D: Direction + Debug
+{
+    type Direction = D;
+
+    #[cfg(feature = "view3d")]
+    fn get_name(&self) -> String {
+        self.data().name.clone()
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)> {
+        let (vertices, indices, texture) = self.data().model.as_ref()?;
+        let vertices = vertices.iter()
+            .map(|(position, tex_coords)| ModelVertex { position: *position, tex_coords: *tex_coords })
+            .collect();
+        Some((vertices, indices.clone(), texture.clone()))
+    }
+
+    #[cfg(feature = "view3d")]
+    fn has_model(&self) -> bool {
+        self.data().model.is_some()
+    }
+}
+
+/// Tiles and rules loaded from a file, so they can be changed without recompiling.
+///
+/// The file has a list of tiles, where every tile has a name, the layers where it can be placed (every layer by default),
+/// its weight (1 by default) and the tiles it allows in every direction. See [TileSetConfig].
+/// Every rule must be bidirectional, or loading fails with [DataError::OneWayRule].
+///
+/// Files can be loaded from JSON, RON and TOML with the features of the same name,
+/// or from any other format that serde supports with [DataTileSet::from_config()].
+///
+/// The tiles are freed once the set, its boards and its tiles are dropped
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use procedural::MaybeTile;
+/// use procedural::data::DataTileSet;
+/// use procedural::directions::Direction4;
+/// let tiles = DataTileSet::<Direction4>::from_json(r#"{ "tiles": [
+///     { "name": "Grass", "weight": 3, "neighbours": {
+///         "North": ["Grass", "Sand"], "East": ["Grass", "Sand"], "South": ["Grass", "Sand"], "West": ["Grass", "Sand"]
+///     } },
+///     { "name": "Sand", "neighbours": {
+///         "North": ["Grass", "Sand", "Water"], "East": ["Grass", "Sand", "Water"],
+///         "South": ["Grass", "Sand", "Water"], "West": ["Grass", "Sand", "Water"]
+///     } },
+///     { "name": "Water", "neighbours": {
+///         "North": ["Sand", "Water"], "East": ["Sand", "Water"], "South": ["Sand", "Water"], "West": ["Sand", "Water"]
+///     } }
+/// ] }"#).unwrap();
+/// let mut board = tiles.board(10, 10, 1);
+/// board.set_tile(MaybeTile::Decided(tiles.tile("Water").unwrap()), 0, 0, 0).unwrap();
+/// board.generate().unwrap();
+///
+/// let error = DataTileSet::<Direction4>::from_json(r#"{ "tiles": [
+///     { "name": "Grass", "neighbours": { "Nort": ["Grass"] } }
+/// ] }"#).err().unwrap();
+/// assert_eq!(error.to_string(), r#"tile "Grass" has neighbours to the "Nort", but there is no such direction"#);
+/// # }
+/// ```
+pub struct DataTileSet<D>
+where
+    D: Direction + Debug
+{
+    tiles: Vec<DataTile<D>>,
+    rules: RuleSet<DataTile<D>>
+}
+
+impl<D> DataTileSet<D>
+where
+    D: Direction + Debug
+{
+    /// Loads a tile set from a JSON file
+    #[cfg(feature = "json")]
+    pub fn from_json(text: &str) -> Result<DataTileSet<D>, DataError> {
+        let config = serde_json::from_str(text).map_err(|error| DataError::Parse(error.to_string()))?;
+        DataTileSet::from_config(config)
+    }
+
+    /// Loads a tile set from a RON file
+    #[cfg(feature = "ron")]
+    pub fn from_ron(text: &str) -> Result<DataTileSet<D>, DataError> {
+        let config = ron::from_str(text).map_err(|error| DataError::Parse(error.to_string()))?;
+        DataTileSet::from_config(config)
+    }
+
+    /// Loads a tile set from a TOML file
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<DataTileSet<D>, DataError> {
+        let config = toml::from_str(text).map_err(|error| DataError::Parse(error.to_string()))?;
+        DataTileSet::from_config(config)
+    }
+
+    /// Checks a tile set that has already been read and builds its rules
+    pub fn from_config(config: TileSetConfig) -> Result<DataTileSet<D>, DataError> {
+        if config.tiles.is_empty() {
+            return Err(DataError::NoTiles)
+        }
+        let mut index = HashMap::new();
+        for (i, tile) in config.tiles.iter().enumerate() {
+            if index.insert(tile.name.as_str(), i).is_some() {
+                return Err(DataError::DuplicateTile(tile.name.clone()))
+            }
+        }
+
+        let directions = D::all();
+        let names = directions.iter().map(|direction| format!("{direction:?}")).collect::<Vec<_>>();
+        let mut allowed = vec![vec![TileSet::new(); directions.len()]; config.tiles.len()];
+        for (i, tile) in config.tiles.iter().enumerate() {
+            for (direction, neighbours) in tile.neighbours.iter() {
+                let d = names.iter().position(|name| name == direction).ok_or_else(|| DataError::UnknownDirection {
+                    tile: tile.name.clone(),
+                    direction: direction.clone()
+                })?;
+                for neighbour in neighbours {
+                    let j = *index.get(neighbour.as_str()).ok_or_else(|| DataError::UnknownNeighbour {
+                        tile: tile.name.clone(),
+                        direction: direction.clone(),
+                        neighbour: neighbour.clone()
+                    })?;
+                    allowed[i][d].insert(j)
+                }
+            }
+        }
+        for (i, tile) in config.tiles.iter().enumerate() {
+            for (d, direction) in directions.iter().enumerate() {
+                let opposite = format!("{:?}", direction.opposite());
+                let o = names.iter().position(|name| *name == opposite).expect("the opposite is not in Direction::all()");
+                if let Some(j) = allowed[i][d].iter().find(|j| !allowed[*j][o].contains(i)) {
+                    return Err(DataError::OneWayRule {
+                        tile: tile.name.clone(),
+                        direction: names[d].clone(),
+                        neighbour: config.tiles[j].name.clone(),
+                        opposite
+                    })
+                }
+            }
+        }
+
+        // One entry for every layer that is mentioned, and one more for all the layers above them
+        let mut layers = config.tiles.iter()
+            .flat_map(|tile| tile.layers.iter().flatten().map(|layer| layer + 1))
+            .chain(config.tiles.iter().map(|tile| match &tile.weight {
+                Weight::Same(_) => 0,
+                Weight::PerLayer(weights) => weights.len(),
+            }))
+            .max()
+            .unwrap_or(0);
+        layers += 1;
+        let mut table = vec![(TileSet::new(), vec![0; config.tiles.len()]); layers];
+        for (i, tile) in config.tiles.iter().enumerate() {
+            // Option::is_none_or() needs Rust 1.82
+            #[allow(clippy::unnecessary_map_or)]
+            for (layer, (possibles, weights)) in table.iter_mut().enumerate() {
+                if tile.layers.as_ref().map_or(true, |layers| layers.contains(&layer)) {
+                    possibles.insert(i)
+                }
+                weights[i] = match &tile.weight {
+                    Weight::Same(weight) => *weight,
+                    Weight::PerLayer(weights) => *weights.get(layer)
+                        .or(weights.last())
+                        .ok_or_else(|| DataError::NoWeights(tile.name.clone()))?,
+                };
+            }
+        }
+
+        let mut data = vec![];
+        for tile in config.tiles {
+            #[cfg(feature = "view3d")]
+            let model = match (tile.model, tile.texture) {
+                (Some(model), Some(texture)) => {
+                    let (vertices, indices) = load_model(&model).map_err(|reason| DataError::BadModel {
+                        tile: tile.name.clone(),
+                        reason
+                    })?;
+                    Some((vertices, indices, texture))
+                },
+                (Some(_), None) => return Err(DataError::MissingTexture(tile.name)),
+                (None, _) => None,
+            };
+            #[cfg(not(feature = "view3d"))]
+            if tile.model.is_some() && tile.texture.is_none() {
+                return Err(DataError::MissingTexture(tile.name))
+            }
+            data.push(TileData {
+                name: tile.name,
+                #[cfg(feature = "view3d")]
+                model
+            })
+        }
+        let data: Arc<[TileData]> = data.into();
+        let tiles = (0..data.len())
+            .map(|index| DataTile { table: Arc::clone(&data), index, direction: PhantomData })
+            .collect::<Vec<_>>();
+
+        Ok(DataTileSet {
+            rules: RuleSet {
+                rules: Rules::from_table(tiles.clone(), allowed, table)
+            },
+            tiles
+        })
+    }
+
+    /// The tile with the given name
+    pub fn tile(&self, name: &str) -> Option<DataTile<D>> {
+        self.tiles.iter().find(|tile| tile.name() == name).cloned()
+    }
+
+    /// All the tiles, in the order of the file
+    pub fn tiles(&self) -> impl Iterator<Item = DataTile<D>> + '_ {
+        self.tiles.iter().cloned()
+    }
+
+    /// The rules of the tile set. See [Board::with_rule_set()]
+    pub fn rule_set(&self) -> RuleSet<DataTile<D>> {
+        self.rules.clone()
+    }
+
+    /// An empty board that uses this tile set
+    pub fn board(&self, width: u32, length: u32, height: u32) -> Board<DataTile<D>> {
+        Board::with_rule_set(width, length, height, self.rule_set())
+    }
+}
+
+/// Reads the vertices (with their texture coordinates) and triangles of a Wavefront OBJ file in `resources/models`
+#[cfg(feature = "view3d")]
+fn load_model(name: &str) -> Result<(Vec<([f32; 3], [f32; 2])>, Vec<u32>), String> {
+    let path = Path::new("resources").join("models").join(name);
+    let text = std::fs::read_to_string(&path).map_err(|error| format!("can't read {}: {error}", path.display()))?;
+    let numbers = |values: std::str::SplitWhitespace, line: usize| values
+        .map(|value| value.parse::<f32>().map_err(|_| format!("line {line}: {value} is not a number")))
+        .collect::<Result<Vec<_>, _>>();
+
+    let mut positions = vec![];
+    let mut tex_coords = vec![];
+    let mut vertices = vec![];
+    let mut known = HashMap::new();
+    let mut indices = vec![];
+    for (line, text) in text.lines().enumerate() {
+        let line = line + 1;
+        let mut values = text.split_whitespace();
+        match values.next() {
+            Some("v") => match numbers(values, line)?[..] {
+                [x, y, z, ..] => positions.push([x, y, z]),
+                _ => return Err(format!("line {line}: a vertex needs 3 coordinates")),
+            },
+            // OBJ files have the origin of the texture at the bottom, and textures have it at the top
+            Some("vt") => match numbers(values, line)?[..] {
+                [u, v, ..] => tex_coords.push([u, 1.0 - v]),
+                _ => return Err(format!("line {line}: a texture coordinate needs 2 values")),
+            },
+            Some("f") => {
+                let mut face = vec![];
+                for corner in values {
+                    let mut parts = corner.split('/').map(|part| part.parse::<usize>().ok());
+                    let (position, tex_coord) = match (parts.next().flatten(), parts.next().flatten()) {
+                        (Some(position), Some(tex_coord)) if position > 0 && tex_coord > 0 => (position - 1, tex_coord - 1),
+                        _ => return Err(format!("line {line}: {corner} is not a vertex/texture coordinate pair")),
+                    };
+                    let vertex = match (positions.get(position), tex_coords.get(tex_coord)) {
+                        (Some(position), Some(tex_coord)) => (*position, *tex_coord),
+                        _ => return Err(format!("line {line}: {corner} refers to something that isn't defined yet")),
+                    };
+                    let i = *known.entry((position, tex_coord)).or_insert_with(|| {
+                        vertices.push(vertex);
+                        vertices.len() as u32 - 1
+                    });
+                    face.push(i)
+                }
+                // Faces with more than 3 corners are split in triangles that share the first corner
+                for k in 2..face.len() {
+                    indices.extend([face[0], face[k - 1], face[k]])
+                }
+            },
+            _ => (),
+        }
+    }
+    Ok((vertices, indices))
+}
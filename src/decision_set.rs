@@ -0,0 +1,168 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+const BITS: usize = u64::BITS as usize;
+
+/// Set of decisions, where each decision is represented by its position in the decision stack.
+///
+/// Unlike a [TileSet](crate::tile_set::TileSet), it only keeps the words that have any decision,
+/// so a set with a few decisions stays small however deep the decision stack is.
+/// The words are shared between clones, and a union that doesn't add anything to one of the sets
+/// reuses that set instead of copying it. Every slot of the board has one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<(u32, u64)>", into = "Vec<(u32, u64)>"))]
+pub(crate) struct DecisionSet {
+    /// The index and bits of every word with any decision, in ascending order of index. None if there is no decision
+    words: Option<Arc<[(u32, u64)]>>
+}
+
+impl DecisionSet {
+    /// A set without any decision
+    pub(crate) fn new() -> DecisionSet {
+        DecisionSet { words: None }
+    }
+
+    fn words(&self) -> &[(u32, u64)] {
+        self.words.as_deref().unwrap_or(&[])
+    }
+
+    pub(crate) fn remove(&mut self, decision: usize) {
+        let index = (decision / BITS) as u32;
+        let bit = 1 << (decision % BITS);
+        if self.words().iter().any(|(i, bits)| *i == index && bits & bit != 0) {
+            *self = DecisionSet::from(self.words().iter()
+                .map(|(i, bits)| (*i, if *i == index { bits & !bit } else { *bits }))
+                .collect::<Vec<_>>())
+        }
+    }
+
+    /// Adds all the decisions of `other`
+    pub(crate) fn union_with(&mut self, other: &DecisionSet) {
+        if other.is_subset(self) {
+            return
+        }
+        if self.is_subset(other) {
+            self.clone_from(other);
+            return
+        }
+        let (mine, theirs) = (self.words(), other.words());
+        let mut words = Vec::with_capacity(mine.len() + theirs.len());
+        let (mut i, mut j) = (0, 0);
+        while i < mine.len() && j < theirs.len() {
+            match mine[i].0.cmp(&theirs[j].0) {
+                Ordering::Less => { words.push(mine[i]); i += 1 }
+                Ordering::Greater => { words.push(theirs[j]); j += 1 }
+                Ordering::Equal => { words.push((mine[i].0, mine[i].1 | theirs[j].1)); i += 1; j += 1 }
+            }
+        }
+        words.extend_from_slice(&mine[i..]);
+        words.extend_from_slice(&theirs[j..]);
+        *self = DecisionSet::from(words)
+    }
+
+    /// If all the decisions of this set are also in `other`
+    fn is_subset(&self, other: &DecisionSet) -> bool {
+        if let (Some(mine), Some(theirs)) = (&self.words, &other.words) {
+            if Arc::ptr_eq(mine, theirs) {
+                return true
+            }
+        }
+        let theirs = other.words();
+        let mut j = 0;
+        self.words().iter().all(|(index, bits)| {
+            while j < theirs.len() && theirs[j].0 < *index {
+                j += 1
+            }
+            j < theirs.len() && theirs[j].0 == *index && bits & !theirs[j].1 == 0
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.words.is_none()
+    }
+
+    /// The highest position in this set
+    pub(crate) fn last(&self) -> Option<usize> {
+        self.words().last().map(|(index, bits)| *index as usize * BITS + (BITS - 1 - bits.leading_zeros() as usize))
+    }
+}
+
+impl From<Vec<(u32, u64)>> for DecisionSet {
+    /// Makes a set from words in ascending order of index, leaving out the empty ones
+    fn from(mut words: Vec<(u32, u64)>) -> DecisionSet {
+        words.retain(|(_, bits)| *bits != 0);
+        DecisionSet { words: (!words.is_empty()).then(|| words.into()) }
+    }
+}
+
+impl From<DecisionSet> for Vec<(u32, u64)> {
+    fn from(set: DecisionSet) -> Vec<(u32, u64)> {
+        set.words().to_vec()
+    }
+}
+
+impl FromIterator<usize> for DecisionSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> DecisionSet {
+        let mut decisions = iter.into_iter().collect::<Vec<_>>();
+        decisions.sort_unstable();
+        let mut words: Vec<(u32, u64)> = Vec::new();
+        for decision in decisions {
+            let index = (decision / BITS) as u32;
+            match words.last_mut() {
+                Some((last, bits)) if *last == index => *bits |= 1 << (decision % BITS),
+                _ => words.push((index, 1 << (decision % BITS))),
+            }
+        }
+        DecisionSet::from(words)
+    }
+}
+
+/// Union of many [DecisionSet]s, which are added to it in place.
+/// It keeps a word for every 64 positions up to the highest one, so it's meant for a few unions that cover the whole board.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct DecisionUnion {
+    words: Vec<u64>
+}
+
+impl DecisionUnion {
+    /// A union without any decision
+    pub(crate) fn new() -> DecisionUnion {
+        DecisionUnion { words: vec![] }
+    }
+
+    /// Adds the decisions of `set`, and returns the ones that weren't in the union yet
+    pub(crate) fn add(&mut self, set: &DecisionSet) -> DecisionSet {
+        let mut added = vec![];
+        for (index, bits) in set.words() {
+            let index = *index as usize;
+            if index >= self.words.len() {
+                self.words.resize(index + 1, 0)
+            }
+            let new = bits & !self.words[index];
+            if new != 0 {
+                self.words[index] |= new;
+                added.push((index as u32, new))
+            }
+        }
+        DecisionSet::from(added)
+    }
+
+    /// Removes the decisions of `set`
+    pub(crate) fn remove(&mut self, set: &DecisionSet) {
+        for (index, bits) in set.words() {
+            if let Some(word) = self.words.get_mut(*index as usize) {
+                *word &= !bits
+            }
+        }
+    }
+
+    /// The decisions of the union
+    pub(crate) fn to_set(&self) -> DecisionSet {
+        DecisionSet::from(self.words.iter().enumerate().map(|(index, bits)| (index as u32, *bits)).collect::<Vec<_>>())
+    }
+}
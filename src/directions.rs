@@ -0,0 +1,450 @@
+//! Ready-made [Direction]s for the most common board shapes, so [Direction::neighbour()] doesn't have to be written by hand.
+//!
+//! The square grid directions can wrap around the edges of the board on any of its axes, for example to make tileable textures
+//! or world maps that can be walked around. By default they don't wrap, and there are aliases for the ones that wrap on all axes.
+//!
+//! For hexagonal boards there are [PointyHex] and [FlatHex].
+//! ```
+//! use procedural::Direction;
+//! use procedural::directions::{Direction4, Toroidal4};
+//! // On a 5x5 board, going west from the first column
+//! assert!(Direction4::<false, false>::West.neighbour(0, 0, 0, 5, 5, 1).is_err());
+//! assert_eq!(Toroidal4::West.neighbour(0, 0, 0, 5, 5, 1).unwrap(), (0, 4, 0));
+//! // Only wrapping east-west, like a world map
+//! assert_eq!(Direction4::<true, false>::West.neighbour(0, 0, 0, 5, 5, 1).unwrap(), (0, 4, 0));
+//! assert!(Direction4::<true, false>::North.neighbour(0, 0, 0, 5, 5, 1).is_err());
+//! ```
+
+use crate::{CoordError, Direction, Layout};
+use crate::symmetry::Rotate;
+
+/// The 4 directions of a 2D grid, without diagonals.
+///
+/// Wraps around the west and east edges if `WRAP_EAST_WEST`, and around the north and south edges if `WRAP_NORTH_SOUTH`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction4<const WRAP_EAST_WEST: bool = false, const WRAP_NORTH_SOUTH: bool = false> {
+    /// Previous row
+    North,
+    /// Next column
+    East,
+    /// Next row
+    South,
+    /// Previous column
+    West
+}
+
+/// [Direction4] that wraps around all edges
+pub type Toroidal4 = Direction4<true, true>;
+
+impl<const WRAP_EAST_WEST: bool, const WRAP_NORTH_SOUTH: bool> Direction for Direction4<WRAP_EAST_WEST, WRAP_NORTH_SOUTH> {
+    fn all() -> Vec<Self> {
+        vec![Direction4::North, Direction4::East, Direction4::South, Direction4::West]
+    }
+
+    fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, _height: u32) -> Result<(usize, usize, usize), CoordError> {
+        let (row_step, col_step) = match self {
+            Direction4::North => (-1, 0),
+            Direction4::East => (0, 1),
+            Direction4::South => (1, 0),
+            Direction4::West => (0, -1),
+        };
+        Ok((
+            step(row, row_step, length, WRAP_NORTH_SOUTH)?,
+            step(col, col_step, width, WRAP_EAST_WEST)?,
+            layer
+        ))
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            Direction4::North => Direction4::South,
+            Direction4::East => Direction4::West,
+            Direction4::South => Direction4::North,
+            Direction4::West => Direction4::East,
+        }
+    }
+}
+
+impl<const WRAP_EAST_WEST: bool, const WRAP_NORTH_SOUTH: bool> Rotate for Direction4<WRAP_EAST_WEST, WRAP_NORTH_SOUTH> {
+    fn rotate(&self) -> Self {
+        match self {
+            Direction4::North => Direction4::East,
+            Direction4::East => Direction4::South,
+            Direction4::South => Direction4::West,
+            Direction4::West => Direction4::North,
+        }
+    }
+
+    fn reflect(&self) -> Self {
+        match self {
+            Direction4::East => Direction4::West,
+            Direction4::West => Direction4::East,
+            other => *other,
+        }
+    }
+}
+
+/// The 8 directions of a 2D grid, including diagonals.
+///
+/// Wraps around the west and east edges if `WRAP_EAST_WEST`, and around the north and south edges if `WRAP_NORTH_SOUTH`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8<const WRAP_EAST_WEST: bool = false, const WRAP_NORTH_SOUTH: bool = false> {
+    /// Previous row
+    North,
+    /// Previous row, next column
+    NorthEast,
+    /// Next column
+    East,
+    /// Next row, next column
+    SouthEast,
+    /// Next row
+    South,
+    /// Next row, previous column
+    SouthWest,
+    /// Previous column
+    West,
+    /// Previous row, previous column
+    NorthWest
+}
+
+/// [Direction8] that wraps around all edges
+pub type Toroidal8 = Direction8<true, true>;
+
+impl<const WRAP_EAST_WEST: bool, const WRAP_NORTH_SOUTH: bool> Direction for Direction8<WRAP_EAST_WEST, WRAP_NORTH_SOUTH> {
+    fn all() -> Vec<Self> {
+        vec![
+            Direction8::North,
+            Direction8::NorthEast,
+            Direction8::East,
+            Direction8::SouthEast,
+            Direction8::South,
+            Direction8::SouthWest,
+            Direction8::West,
+            Direction8::NorthWest
+        ]
+    }
+
+    fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, _height: u32) -> Result<(usize, usize, usize), CoordError> {
+        let (row_step, col_step) = match self {
+            Direction8::North => (-1, 0),
+            Direction8::NorthEast => (-1, 1),
+            Direction8::East => (0, 1),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (1, 0),
+            Direction8::SouthWest => (1, -1),
+            Direction8::West => (0, -1),
+            Direction8::NorthWest => (-1, -1),
+        };
+        Ok((
+            step(row, row_step, length, WRAP_NORTH_SOUTH)?,
+            step(col, col_step, width, WRAP_EAST_WEST)?,
+            layer
+        ))
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            Direction8::North => Direction8::South,
+            Direction8::NorthEast => Direction8::SouthWest,
+            Direction8::East => Direction8::West,
+            Direction8::SouthEast => Direction8::NorthWest,
+            Direction8::South => Direction8::North,
+            Direction8::SouthWest => Direction8::NorthEast,
+            Direction8::West => Direction8::East,
+            Direction8::NorthWest => Direction8::SouthEast,
+        }
+    }
+}
+
+impl<const WRAP_EAST_WEST: bool, const WRAP_NORTH_SOUTH: bool> Rotate for Direction8<WRAP_EAST_WEST, WRAP_NORTH_SOUTH> {
+    fn rotate(&self) -> Self {
+        match self {
+            Direction8::North => Direction8::East,
+            Direction8::NorthEast => Direction8::SouthEast,
+            Direction8::East => Direction8::South,
+            Direction8::SouthEast => Direction8::SouthWest,
+            Direction8::South => Direction8::West,
+            Direction8::SouthWest => Direction8::NorthWest,
+            Direction8::West => Direction8::North,
+            Direction8::NorthWest => Direction8::NorthEast,
+        }
+    }
+
+    fn reflect(&self) -> Self {
+        match self {
+            Direction8::NorthEast => Direction8::NorthWest,
+            Direction8::East => Direction8::West,
+            Direction8::SouthEast => Direction8::SouthWest,
+            Direction8::SouthWest => Direction8::SouthEast,
+            Direction8::West => Direction8::East,
+            Direction8::NorthWest => Direction8::NorthEast,
+            other => *other,
+        }
+    }
+}
+
+/// The 6 directions of a 3D grid, without diagonals.
+///
+/// Wraps around the west and east edges if `WRAP_EAST_WEST`, around the north and south edges if `WRAP_NORTH_SOUTH`
+/// and between the top and bottom layers if `WRAP_UP_DOWN`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction6<const WRAP_EAST_WEST: bool = false, const WRAP_NORTH_SOUTH: bool = false, const WRAP_UP_DOWN: bool = false> {
+    /// Previous row
+    North,
+    /// Next column
+    East,
+    /// Next row
+    South,
+    /// Previous column
+    West,
+    /// Next layer
+    Up,
+    /// Previous layer
+    Down
+}
+
+/// [Direction6] that wraps around all edges
+pub type Toroidal6 = Direction6<true, true, true>;
+
+impl<const WRAP_EAST_WEST: bool, const WRAP_NORTH_SOUTH: bool, const WRAP_UP_DOWN: bool> Direction for Direction6<WRAP_EAST_WEST, WRAP_NORTH_SOUTH, WRAP_UP_DOWN> {
+    fn all() -> Vec<Self> {
+        vec![
+            Direction6::North,
+            Direction6::East,
+            Direction6::South,
+            Direction6::West,
+            Direction6::Up,
+            Direction6::Down
+        ]
+    }
+
+    fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, height: u32) -> Result<(usize, usize, usize), CoordError> {
+        let (row_step, col_step, layer_step) = match self {
+            Direction6::North => (-1, 0, 0),
+            Direction6::East => (0, 1, 0),
+            Direction6::South => (1, 0, 0),
+            Direction6::West => (0, -1, 0),
+            Direction6::Up => (0, 0, 1),
+            Direction6::Down => (0, 0, -1),
+        };
+        Ok((
+            step(row, row_step, length, WRAP_NORTH_SOUTH)?,
+            step(col, col_step, width, WRAP_EAST_WEST)?,
+            step(layer, layer_step, height, WRAP_UP_DOWN)?
+        ))
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            Direction6::North => Direction6::South,
+            Direction6::East => Direction6::West,
+            Direction6::South => Direction6::North,
+            Direction6::West => Direction6::East,
+            Direction6::Up => Direction6::Down,
+            Direction6::Down => Direction6::Up,
+        }
+    }
+}
+
+impl<const WRAP_EAST_WEST: bool, const WRAP_NORTH_SOUTH: bool, const WRAP_UP_DOWN: bool> Rotate for Direction6<WRAP_EAST_WEST, WRAP_NORTH_SOUTH, WRAP_UP_DOWN> {
+    fn rotate(&self) -> Self {
+        match self {
+            Direction6::North => Direction6::East,
+            Direction6::East => Direction6::South,
+            Direction6::South => Direction6::West,
+            Direction6::West => Direction6::North,
+            other => *other,
+        }
+    }
+
+    fn reflect(&self) -> Self {
+        match self {
+            Direction6::East => Direction6::West,
+            Direction6::West => Direction6::East,
+            other => *other,
+        }
+    }
+}
+
+/// The 6 directions of a hexagonal board with pointy-topped hexagons, where odd rows are shifted half a hexagon to the right.
+///
+/// The board is still indexed by row and column, use [PointyHex::to_axial()] and [PointyHex::from_axial()] to work with axial coordinates.
+/// ```
+/// use procedural::Direction;
+/// use procedural::directions::PointyHex;
+/// // Row 1 is shifted to the right, so going north-east from it doesn't stay in the same column
+/// assert_eq!(PointyHex::NorthEast.neighbour(1, 1, 0, 5, 5, 1).unwrap(), (0, 2, 0));
+/// assert_eq!(PointyHex::NorthEast.neighbour(2, 1, 0, 5, 5, 1).unwrap(), (1, 1, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointyHex {
+    /// Same row, next column
+    East,
+    /// Previous row, to the right
+    NorthEast,
+    /// Previous row, to the left
+    NorthWest,
+    /// Same row, previous column
+    West,
+    /// Next row, to the left
+    SouthWest,
+    /// Next row, to the right
+    SouthEast
+}
+
+impl PointyHex {
+    /// Converts a row and column of the board to axial coordinates (q, r)
+    pub fn to_axial(row: usize, col: usize) -> (isize, isize) {
+        let (row, col) = (row as isize, col as isize);
+        (col - (row - (row & 1)) / 2, row)
+    }
+
+    /// Converts axial coordinates (q, r) to a row and column of the board
+    pub fn from_axial(q: isize, r: isize) -> Result<(usize, usize), CoordError> {
+        let col = q + (r - (r & 1)) / 2;
+        match (usize::try_from(r), usize::try_from(col)) {
+            (Ok(row), Ok(col)) => Ok((row, col)),
+            _ => Err(CoordError),
+        }
+    }
+}
+
+impl Direction for PointyHex {
+    fn all() -> Vec<Self> {
+        vec![
+            PointyHex::East,
+            PointyHex::NorthEast,
+            PointyHex::NorthWest,
+            PointyHex::West,
+            PointyHex::SouthWest,
+            PointyHex::SouthEast
+        ]
+    }
+
+    fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, _height: u32) -> Result<(usize, usize, usize), CoordError> {
+        // Going to the right from an odd row, or to the left from an even row, moves one column
+        let shifted = row % 2 == 1;
+        let (row_step, col_step) = match self {
+            PointyHex::East => (0, 1),
+            PointyHex::NorthEast => (-1, shifted as i8),
+            PointyHex::NorthWest => (-1, shifted as i8 - 1),
+            PointyHex::West => (0, -1),
+            PointyHex::SouthWest => (1, shifted as i8 - 1),
+            PointyHex::SouthEast => (1, shifted as i8),
+        };
+        Ok((step(row, row_step, length, false)?, step(col, col_step, width, false)?, layer))
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            PointyHex::East => PointyHex::West,
+            PointyHex::NorthEast => PointyHex::SouthWest,
+            PointyHex::NorthWest => PointyHex::SouthEast,
+            PointyHex::West => PointyHex::East,
+            PointyHex::SouthWest => PointyHex::NorthEast,
+            PointyHex::SouthEast => PointyHex::NorthWest,
+        }
+    }
+
+    fn layout() -> Layout {
+        Layout::StaggeredRows
+    }
+}
+
+/// The 6 directions of a hexagonal board with flat-topped hexagons, where odd columns are shifted half a hexagon down.
+///
+/// The board is still indexed by row and column, use [FlatHex::to_axial()] and [FlatHex::from_axial()] to work with axial coordinates.
+/// ```
+/// use procedural::Direction;
+/// use procedural::directions::FlatHex;
+/// // Column 1 is shifted down, so going south-east from it doesn't stay in the same row
+/// assert_eq!(FlatHex::SouthEast.neighbour(1, 1, 0, 5, 5, 1).unwrap(), (2, 2, 0));
+/// assert_eq!(FlatHex::SouthEast.neighbour(1, 2, 0, 5, 5, 1).unwrap(), (1, 3, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlatHex {
+    /// Previous row, same column
+    North,
+    /// Next column, upwards
+    NorthEast,
+    /// Next column, downwards
+    SouthEast,
+    /// Next row, same column
+    South,
+    /// Previous column, downwards
+    SouthWest,
+    /// Previous column, upwards
+    NorthWest
+}
+
+impl FlatHex {
+    /// Converts a row and column of the board to axial coordinates (q, r)
+    pub fn to_axial(row: usize, col: usize) -> (isize, isize) {
+        let (row, col) = (row as isize, col as isize);
+        (col, row - (col - (col & 1)) / 2)
+    }
+
+    /// Converts axial coordinates (q, r) to a row and column of the board
+    pub fn from_axial(q: isize, r: isize) -> Result<(usize, usize), CoordError> {
+        let row = r + (q - (q & 1)) / 2;
+        match (usize::try_from(row), usize::try_from(q)) {
+            (Ok(row), Ok(col)) => Ok((row, col)),
+            _ => Err(CoordError),
+        }
+    }
+}
+
+impl Direction for FlatHex {
+    fn all() -> Vec<Self> {
+        vec![
+            FlatHex::North,
+            FlatHex::NorthEast,
+            FlatHex::SouthEast,
+            FlatHex::South,
+            FlatHex::SouthWest,
+            FlatHex::NorthWest
+        ]
+    }
+
+    fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, _height: u32) -> Result<(usize, usize, usize), CoordError> {
+        // Going down from an odd column, or up from an even column, moves one row
+        let shifted = col % 2 == 1;
+        let (row_step, col_step) = match self {
+            FlatHex::North => (-1, 0),
+            FlatHex::NorthEast => (shifted as i8 - 1, 1),
+            FlatHex::SouthEast => (shifted as i8, 1),
+            FlatHex::South => (1, 0),
+            FlatHex::SouthWest => (shifted as i8, -1),
+            FlatHex::NorthWest => (shifted as i8 - 1, -1),
+        };
+        Ok((step(row, row_step, length, false)?, step(col, col_step, width, false)?, layer))
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            FlatHex::North => FlatHex::South,
+            FlatHex::NorthEast => FlatHex::SouthWest,
+            FlatHex::SouthEast => FlatHex::NorthWest,
+            FlatHex::South => FlatHex::North,
+            FlatHex::SouthWest => FlatHex::NorthEast,
+            FlatHex::NorthWest => FlatHex::SouthEast,
+        }
+    }
+
+    fn layout() -> Layout {
+        Layout::StaggeredColumns
+    }
+}
+
+/// Moves `position` by `amount` (-1, 0 or 1) along an axis of the given size
+fn step(position: usize, amount: i8, size: u32, wrap: bool) -> Result<usize, CoordError> {
+    let size = size as usize;
+    match amount {
+        -1 if position > 0 => Ok(position - 1),
+        -1 if wrap => Ok(size - 1),
+        1 if position + 1 < size => Ok(position + 1),
+        1 if wrap => Ok(0),
+        0 => Ok(position),
+        _ => Err(CoordError),
+    }
+}
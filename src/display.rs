@@ -1,10 +1,10 @@
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
 
-use crate::{MaybeTile, Board, Tile};
+use crate::{MaybeTile, Board, BoardTile, Tile, Direction, Layout, ImpossibleBoardError, OutOfBoardError, DifferentTilesError, BadPlacementError, Count, CountError, ConnectionError, CoordError, ValidationReport};
 
 impl<T> Display for MaybeTile<T>
 where
-    T: Display + Tile
+    T: Display + BoardTile
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -16,16 +16,200 @@ where
 
 impl<T> Display for Board<T>
 where
-    T: Display + Tile
+    T: Display + BoardTile
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.tiles[0] {
-            for col in row {
-                write!(f, "{}", col)?
-            }
-            write!(f, "\n")?
+        match T::Direction::layout() {
+            Layout::Square => for row in &self.tiles[0] {
+                for col in row {
+                    write!(f, "{}", self.to_maybe_tile(col))?
+                }
+                write!(f, "\n")?
+            },
+            Layout::StaggeredRows => for (i, row) in self.tiles[0].iter().enumerate() {
+                if i % 2 == 1 {
+                    write!(f, " ")?
+                }
+                for col in row {
+                    write!(f, "{} ", self.to_maybe_tile(col))?
+                }
+                writeln!(f)?
+            },
+            Layout::StaggeredColumns => for row in &self.tiles[0] {
+                // Even columns on one line, and odd columns half a slot lower, on the next one
+                for parity in [0, 1] {
+                    for (j, col) in row.iter().enumerate() {
+                        if j % 2 == parity {
+                            write!(f, "{}", self.to_maybe_tile(col))?
+                        } else {
+                            write!(f, " ")?
+                        }
+                    }
+                    writeln!(f)?
+                }
+            },
         };
 
         std::fmt::Result::Ok(())
     }
 }
+
+impl<T> Display for ImpossibleBoardError<T>
+where
+    T: BoardTile
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "there is no board that meets all rules")?;
+        if let (Some((row, col, layer)), Some((_, count))) = (self.coord, &self.count) {
+            write!(f, ", the last contradiction was that a tile couldn't be in {count} slots, found at row {row}, col {col}, layer {layer}")?;
+        } else if let Some((row, col, layer)) = self.coord {
+            write!(f, ", the last contradiction was at row {row}, col {col}, layer {layer}")?;
+            if !self.constraints.is_empty() {
+                let neighbours = self.constraints.iter()
+                    .map(|constraint| format!("{:?}", constraint.coord))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, " due to the neighbours at {neighbours}")?;
+            }
+        }
+        write!(f, " (after going back {} times)", self.backtracks)
+    }
+}
+
+impl Display for OutOfBoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the position is outside of the board")
+    }
+}
+
+impl Display for DifferentTilesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the rule set doesn't have the same tiles as the board")
+    }
+}
+
+impl Display for BadPlacementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BadPlacementError::TileOccupied => write!(f, "there is already a different tile in that position"),
+            BadPlacementError::TileAlreadyPlaced => write!(f, "that tile was already placed in that position"),
+            BadPlacementError::ImpossibleTile => write!(f, "the tile can't be placed there due to the surrounding tiles' rules"),
+            BadPlacementError::NotAllPossible => write!(f, "not all of the tiles can be placed there due to the surrounding tiles' rules"),
+        }
+    }
+}
+
+impl Display for Count {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (min, max) if min == max => write!(f, "exactly {min}"),
+            (min, usize::MAX) => write!(f, "at least {min}"),
+            (0, max) => write!(f, "at most {max}"),
+            (min, max) => write!(f, "between {min} and {max}"),
+        }
+    }
+}
+
+impl Display for CountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CountError::UnknownTile => write!(f, "the tile is not one of the tiles of the board"),
+            CountError::MinGreaterThanMax => write!(f, "the minimum of the count is greater than its maximum"),
+            CountError::NotEnoughSlots => write!(f, "the minimum of the count is greater than the amount of slots of the board"),
+        }
+    }
+}
+
+impl Display for ConnectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionError::UnknownTile => write!(f, "one of the tiles is not one of the tiles of the board"),
+            ConnectionError::OutOfBoard => write!(f, "one of the ends of the path is outside of the board"),
+        }
+    }
+}
+
+impl Display for CoordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the position is outside of the board")
+    }
+}
+
+impl<T> Display for ValidationReport<T>
+where
+    T: Tile + Debug,
+    T::Direction: Debug
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (tile, other, direction) in &self.one_way_rules {
+            writeln!(f, "ERROR: Rules are not bidirectional for {tile:?} {other:?} {direction:?}")?
+        }
+        #[cfg(feature = "view3d")]
+        for name in &self.duplicate_names {
+            writeln!(f, "WARNING: There is more than one tile with the name `{name}`")?
+        }
+        for direction in &self.bad_opposites {
+            writeln!(f, "ERROR: The opposite of the opposite of {direction:?} is not {direction:?}")?
+        }
+        for (direction, position) in &self.bad_neighbours {
+            writeln!(f, "ERROR: Going {direction:?} from {position:?} and then to its opposite doesn't go back to {position:?}")?
+        }
+        for (layer, tile) in &self.unknown_possibles {
+            writeln!(f, "ERROR: {tile:?} is possible in layer {layer} but it's not in Tile::all()")?
+        }
+        for (layer, tile) in &self.zero_weights {
+            writeln!(f, "WARNING: {tile:?} is possible in layer {layer} but its distribution is 0")?
+        }
+
+        std::fmt::Result::Ok(())
+    }
+}
+
+impl<T> Display for crate::chunks::ChunkError<T>
+where
+    T: Tile
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use crate::chunks::ChunkError;
+        match self {
+            ChunkError::Seam { coord: (row, col, layer), error } => {
+                write!(f, "the chunks around don't fit at row {row}, col {col}, layer {layer}: {error}")
+            },
+            ChunkError::Impossible(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<D> Display for crate::data::DataTile<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Display for crate::data::DataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use crate::data::DataError;
+        match self {
+            DataError::Parse(error) => write!(f, "the tile set can't be read: {error}"),
+            DataError::NoTiles => write!(f, "the tile set has no tiles"),
+            DataError::DuplicateTile(tile) => write!(f, "there is more than one tile named \"{tile}\""),
+            DataError::UnknownDirection { tile, direction } => write!(
+                f,
+                "tile \"{tile}\" has neighbours to the \"{direction}\", but there is no such direction"
+            ),
+            DataError::UnknownNeighbour { tile, direction, neighbour } => write!(
+                f,
+                "tile \"{tile}\" allows \"{neighbour}\" to the {direction}, but there is no tile with that name"
+            ),
+            DataError::OneWayRule { tile, direction, neighbour, opposite } => write!(
+                f,
+                "tile \"{tile}\" allows \"{neighbour}\" to the {direction}, but \"{neighbour}\" doesn't allow \"{tile}\" to the {opposite}"
+            ),
+            DataError::NoWeights(tile) => write!(f, "tile \"{tile}\" has an empty list of weights"),
+            DataError::MissingTexture(tile) => write!(f, "tile \"{tile}\" has a model but no texture"),
+            DataError::BadModel { tile, reason } => write!(f, "the model of tile \"{tile}\" can't be loaded: {reason}"),
+        }
+    }
+}
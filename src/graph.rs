@@ -0,0 +1,131 @@
+//! Boards that are not grids, like a dungeon made of rooms or a road network. See [Graph]
+
+use std::marker::PhantomData;
+
+use crate::Direction;
+
+/// Nodes connected by edges, where every edge is labelled with a [Direction].
+///
+/// A board created with [Board::from_graph()](crate::Board::from_graph()) has a slot for each node,
+/// and the rules of the tiles are checked between every pair of connected nodes, using the label of the edge as the direction.
+/// Node `n` is at row 0, column `n`, layer 0 of the board.
+/// ```
+/// use std::collections::HashSet;
+/// use procedural::{Board, CoordError, Direction, MaybeTile, Tile};
+/// use procedural::graph::Graph;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Link {
+///     Door
+/// }
+///
+/// impl Direction for Link {
+///     fn all() -> Vec<Self> {
+///         vec![Link::Door]
+///     }
+///
+///     fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, height: u32) -> Result<(usize, usize, usize), CoordError> {
+///         // Never called, the board uses the edges of the graph instead
+///         Err(CoordError)
+///     }
+///
+///     fn opposite(&self) -> Self {
+///         Link::Door
+///     }
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Room {
+///     Treasure,
+///     Monster
+/// }
+///
+/// impl Tile for Room {
+///     type Direction = Link;
+///
+///     fn all() -> HashSet<Self> {
+///         HashSet::from([Room::Treasure, Room::Monster])
+///     }
+///
+///     fn possibles(_layer: usize) -> HashSet<Self> {
+///         Self::all()
+///     }
+///
+///     fn get_rules(&self) -> Box<dyn Fn(&Self, Link) -> bool + '_> {
+///         // Every treasure is guarded by monsters
+///         Box::new(move |other: &Room, _: Link| *self == Room::Monster || *other == Room::Monster)
+///     }
+///
+///     fn get_distribution(&self, _layer: usize) -> u32 {
+///         1
+///     }
+/// }
+///
+/// // An entrance (node 0) with doors to 3 other rooms
+/// let mut graph = Graph::new(4);
+/// for room in 1..4 {
+///     graph.connect(0, room, Link::Door);
+/// }
+/// let mut board = Board::<Room>::from_graph(&graph);
+/// board.set_tile(MaybeTile::Decided(Room::Treasure), 0, 0, 0).unwrap();
+/// board.generate().unwrap();
+/// for room in 1..4 {
+///     assert_eq!(board.get_tile(0, room, 0).unwrap(), MaybeTile::Decided(Room::Monster));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Graph<D>
+where
+    D: Direction
+{
+    /// `edges[node]` has the index of the direction and the node at the other end of every edge that starts in `node`
+    pub(crate) edges: Vec<Vec<(usize, usize)>>,
+    direction: PhantomData<D>
+}
+
+impl<D> Graph<D>
+where
+    D: Direction + PartialEq
+{
+    /// A graph with `nodes` nodes and no edges
+    pub fn new(nodes: usize) -> Graph<D> {
+        Graph {
+            edges: vec![vec![]; nodes],
+            direction: PhantomData
+        }
+    }
+
+    /// How many nodes the graph has
+    pub fn nodes(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Connects two nodes, so `to` is in `direction` from `from`, and `from` is in `direction.opposite()` from `to`
+    ///
+    /// # Panics
+    /// If any of the nodes is not in the graph, or `direction` is not in [Direction::all()]
+    pub fn connect(&mut self, from: usize, to: usize, direction: D) {
+        self.connect_with(from, to, direction, direction.opposite())
+    }
+
+    /// Like [Graph::connect()], but the way back from `to` to `from` is in direction `back`
+    /// instead of the opposite of `direction`
+    ///
+    /// # Panics
+    /// If any of the nodes is not in the graph, or any of the directions is not in [Direction::all()]
+    pub fn connect_with(&mut self, from: usize, to: usize, direction: D, back: D) {
+        assert!(from < self.nodes() && to < self.nodes(), "node out of the graph");
+        self.edges[from].push((index(direction), to));
+        self.edges[to].push((index(back), from));
+    }
+}
+
+/// The index of `direction` in [Direction::all()]
+fn index<D>(direction: D) -> usize
+where
+    D: Direction + PartialEq
+{
+    D::all().iter()
+        .position(|other| *other == direction)
+        .expect("the direction is not in Direction::all()")
+}
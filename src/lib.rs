@@ -144,15 +144,21 @@
 #![deny(missing_docs)]
 #![deny(missing_doc_code_examples)]
 
+use std::collections::{BTreeSet, VecDeque};
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::{collections::HashSet};
+use std::time::{Duration, Instant};
 #[cfg(feature = "view3d")]
 use std::path::Path;
 
 use rand::distributions::WeightedIndex;
 use rand::prelude::Distribution;
 use rand::seq::IteratorRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 #[cfg(feature = "view3d")]
 use te_renderer::model::ModelVertex;
 #[cfg(feature = "view3d")]
@@ -160,33 +166,74 @@ use te_renderer::state::GpuState;
 #[cfg(feature = "view3d")]
 use te_renderer::state::TeState;
 
+mod candidates;
+pub mod chunks;
+#[cfg(feature = "serde")]
+pub mod data;
+mod decision_set;
+pub mod directions;
 mod display;
+pub mod graph;
+pub mod observer;
+#[cfg(feature = "overlapping")]
+pub mod overlapping;
+mod rule_set;
+mod rules;
+pub mod selection;
+pub mod sockets;
+pub mod symmetry;
+mod tile_set;
+mod validate;
+
+use candidates::Candidates;
+use decision_set::{DecisionSet, DecisionUnion};
+use graph::Graph;
+use observer::{Control, Event};
+pub use observer::Observer;
+pub use rule_set::RuleSet;
+use rules::Rules;
+pub use selection::{CellSelector, TileSelector};
+use tile_set::TileSet;
+pub use validate::{validate, validate_with_size, ValidationReport};
 
 #[derive(Debug)]
-struct DecisionBranch<T>
-where
-    T: Tile
-{
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct DecisionBranch {
     deciding_coord: (usize, usize, usize),
-    tried_tiles: HashSet<T>,
-    dead_ends: Vec<(usize, usize, usize)>,
-    temp_decided_coords: HashSet<(usize, usize, usize)>
+    tried_tiles: TileSet,
+    /// The decisions that made the tried tiles fail, so generating can go back to them when there are no tiles left to try
+    conflicts: DecisionSet,
+    /// The previous state and culprits of every slot changed in this branch, in the order they were changed
+    trail: Vec<((usize, usize, usize), Slot, DecisionSet)>,
+    /// The culprits added to every limited tile in this branch, by the index of its limit. See [TileCount::blame()]
+    count_trail: Vec<(usize, bool, DecisionSet)>
 }
 
-impl<T> DecisionBranch<T>
-where
-    T: Tile
-{
-    fn new(row: usize, col: usize, layer: usize) -> DecisionBranch<T> {
+impl DecisionBranch {
+    fn new(row: usize, col: usize, layer: usize) -> DecisionBranch {
         DecisionBranch {
             deciding_coord: (row, col, layer),
-            tried_tiles: HashSet::new(),
-            dead_ends: vec![],
-            temp_decided_coords: HashSet::new()
+            tried_tiles: TileSet::new(),
+            conflicts: DecisionSet::new(),
+            trail: vec![],
+            count_trail: vec![]
         }
     }
 }
 
+/// A slot that has been left without possibilities
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Contradiction {
+    coord: (usize, usize, usize),
+    /// The index of the direction, position and possibilities of every neighbour that removed possibilities from the slot
+    constraints: Vec<(usize, (usize, usize, usize), TileSet)>,
+    /// The decisions that led to this contradiction, by their position in the decision stack
+    culprits: DecisionSet,
+    /// The limited tile, by its index in [Rules], and its limit, if the contradiction is that limit
+    count: Option<(usize, Count)>
+}
+
 /// The status of the current decision branch
 pub enum BranchStatus {
     /// The board is complete and no generating has to be done
@@ -199,14 +246,293 @@ pub enum BranchStatus {
     CompleteLayer
 }
 
+/// How much generating can be done in a single call to [Board::generate_with_budget()].
+/// Generating stops as soon as any of the limits is reached
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Budget {
+    /// Stop generating after this moment
+    pub deadline: Option<Instant>,
+    /// Stop generating after going back this many times
+    pub max_backtracks: Option<u64>
+}
+
+impl Budget {
+    /// Generate for at most `duration`, starting now
+    pub fn time(duration: Duration) -> Budget {
+        Budget {
+            deadline: Some(Instant::now() + duration),
+            max_backtracks: None
+        }
+    }
+
+    /// Generate until going back `max_backtracks` times
+    pub fn backtracks(max_backtracks: u64) -> Budget {
+        Budget {
+            deadline: None,
+            max_backtracks: Some(max_backtracks)
+        }
+    }
+
+    fn is_exhausted(&self, backtracks: u64) -> bool {
+        let late = match self.deadline {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        };
+        let stuck = match self.max_backtracks {
+            Some(max_backtracks) => backtracks >= max_backtracks,
+            None => false,
+        };
+        late || stuck
+    }
+}
+
+/// When generating should give up on the current board and start over. See [Board::set_restart_policy()]
+///
+/// Starting over keeps the tiles placed with [Board::set_tile()], and the random state is different every time,
+/// so a board that got stuck in a bad region gets a new chance instead of going back one decision at a time.
+/// The limits grow with every restart, so an impossible board is still detected eventually.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RestartPolicy {
+    /// Never start over. This is the default
+    #[default]
+    Never,
+    /// Start over after `unit` times the [Luby sequence](https://doi.org/10.1016/0020-0190(93)90029-9) backtracks:
+    /// unit, unit, 2*unit, unit, unit, 2*unit, 4*unit...
+    Luby {
+        /// Backtracks of the shortest attempts. Must be greater than 0
+        unit: u64
+    },
+    /// Start over after first, first*factor, first*factor²... backtracks
+    Geometric {
+        /// Backtracks of the first attempt. Must be greater than 0
+        first: u64,
+        /// How much the backtracks grow after every restart. Must be greater than 1
+        factor: f64
+    }
+}
+
+impl RestartPolicy {
+    /// How many backtracks the attempt after `restarts` restarts can do, or None if it never restarts
+    fn limit(&self, restarts: u32) -> Option<u64> {
+        match self {
+            RestartPolicy::Never => None,
+            RestartPolicy::Luby { unit } => Some(unit.saturating_mul(luby(restarts as u64 + 1))),
+            RestartPolicy::Geometric { first, factor } => Some((*first as f64 * factor.powf(restarts as f64)) as u64),
+        }
+    }
+}
+
+/// The `i`th element of the Luby sequence, starting from 1
+fn luby(mut i: u64) -> u64 {
+    loop {
+        let k = u64::BITS - i.leading_zeros();
+        if i == (1 << k) - 1 {
+            return 1 << (k - 1)
+        }
+        i -= (1 << (k - 1)) - 1;
+    }
+}
+
+/// How many slots of the whole board can have a tile. See [Board::set_count()]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Count {
+    /// The tile must be in at least this many slots
+    pub min: usize,
+    /// The tile can't be in more than this many slots
+    pub max: usize
+}
+
+impl Count {
+    /// The tile must be in exactly `n` slots
+    pub fn exactly(n: usize) -> Count {
+        Count { min: n, max: n }
+    }
+
+    /// The tile must be in `n` slots or more
+    pub fn at_least(n: usize) -> Count {
+        Count { min: n, max: usize::MAX }
+    }
+
+    /// The tile can be in `n` slots or fewer
+    pub fn at_most(n: usize) -> Count {
+        Count { min: 0, max: n }
+    }
+}
+
+/// A tile whose amount is limited, and how many slots have it. See [Board::set_count()]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct TileCount {
+    /// By its index in [Rules]
+    tile: usize,
+    count: Count,
+    /// How many slots have the tile decided
+    decided: usize,
+    /// The undecided slots that can still have the tile, by their index in the board
+    undecided: BTreeSet<usize>,
+    /// The decisions that led to the slots that have the tile decided
+    decided_culprits: DecisionUnion,
+    /// The decisions that removed the tile from the slots that can't have it
+    removed_culprits: DecisionUnion
+}
+
+impl TileCount {
+    fn new(tile: usize, count: Count) -> TileCount {
+        TileCount {
+            tile,
+            count,
+            decided: 0,
+            undecided: BTreeSet::new(),
+            decided_culprits: DecisionUnion::new(),
+            removed_culprits: DecisionUnion::new()
+        }
+    }
+
+    /// How many slots have the tile decided or can still have it
+    fn possible(&self) -> usize {
+        self.decided + self.undecided.len()
+    }
+
+    /// Updates the amounts after the slot at `index` changed from `previous` to `slot`. Returns if they changed
+    fn change(&mut self, index: usize, previous: &Slot, slot: &Slot) -> bool {
+        let (was_decided, decided) = (*previous == Slot::Decided(self.tile), *slot == Slot::Decided(self.tile));
+        self.decided = self.decided + decided as usize - was_decided as usize;
+        let can_have = |slot: &Slot| matches!(slot, Slot::Undecided(possibilities) if possibilities.contains(self.tile));
+        let (could_have, can_have) = (can_have(previous), can_have(slot));
+        match (could_have, can_have) {
+            (false, true) => self.undecided.insert(index),
+            (true, false) => self.undecided.remove(&index),
+            _ => false,
+        };
+        was_decided != decided || could_have != can_have
+    }
+
+    /// Adds the culprits of a slot that is now `slot` to the culprits of the tile.
+    /// Returns which of them were added, to the decided culprits if true, so they can be removed again
+    fn blame(&mut self, slot: &Slot, culprits: &DecisionSet) -> Option<(bool, DecisionSet)> {
+        let decided = *slot == Slot::Decided(self.tile);
+        let added = match decided {
+            true => self.decided_culprits.add(culprits),
+            false if !slot.contains(self.tile) => self.removed_culprits.add(culprits),
+            false => return None,
+        };
+        (!added.is_empty()).then_some((decided, added))
+    }
+}
+
+/// The result of [Board::generate_with_budget()]
 #[derive(Debug)]
+pub enum GenerationOutcome<T>
+where
+    T: BoardTile
+{
+    /// The board is full
+    Complete,
+    /// The [Budget] ran out before the board was full. Generating again will continue from there
+    BudgetExhausted,
+    /// An [Observer] cancelled the generation. Generating again will continue from there
+    Cancelled,
+    /// There is no board that meets all rules
+    Impossible(ImpossibleBoardError<T>)
+}
+
 /// Due to the rules/directions/tiles/size of this board, there is no combination of tiles that meet all rules
-pub struct ImpossibleBoardError;
+/// ```
+/// # use std::collections::HashSet;
+/// # use procedural::directions::Direction6;
+/// # #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// # enum Land { Stone, Grass }
+/// # impl procedural::Tile for Land {
+/// #     type Direction = Direction6;
+/// #     fn all() -> HashSet<Self> { HashSet::from([Land::Stone, Land::Grass]) }
+/// // Stone is always below grass
+/// fn possibles(layer: usize) -> HashSet<Self> {
+///     match layer {
+///         0 => HashSet::from([Land::Stone]),
+///         _ => HashSet::from([Land::Grass]),
+///     }
+/// }
+/// // But grass can't grow on stone
+/// fn get_rules(&self) -> Box<dyn Fn(&Self, Direction6) -> bool + '_> {
+///     Box::new(move |other: &Land, direction: Direction6| {
+///         !matches!((self, other, direction), (Land::Stone, Land::Grass, Direction6::Up) | (Land::Grass, Land::Stone, Direction6::Down))
+///     })
+/// }
+/// #     fn get_distribution(&self, _layer: usize) -> u32 { 1 }
+/// # }
+/// let mut board = procedural::Board::<Land>::new(1, 1, 2);
+/// let error = board.generate().unwrap_err();
+/// // The grass slot was left empty by the stone below it
+/// assert_eq!(error.coord, Some((0, 0, 1)));
+/// assert_eq!(error.constraints.len(), 1);
+/// assert_eq!(error.constraints[0].direction, Direction6::Down);
+/// assert_eq!(error.constraints[0].coord, (0, 0, 0));
+/// assert_eq!(error.constraints[0].tiles, vec![Land::Stone]);
+/// // Placing the stone was undone before giving up
+/// assert_eq!(error.backtracks, 1);
+/// ```
+pub struct ImpossibleBoardError<T>
+where
+    T: BoardTile
+{
+    /// The last slot that was left without possibilities, if any.
+    /// If it was because of [ImpossibleBoardError::count], it's the slot whose change was being propagated when the limit couldn't be met,
+    /// which doesn't need to have anything to do with the limited tile
+    pub coord: Option<(usize, usize, usize)>,
+    /// The neighbours of [ImpossibleBoardError::coord] that removed its possibilities
+    pub constraints: Vec<NeighbourConstraint<T>>,
+    /// The tile and the limit of [Board::set_count()] that couldn't be met in the last contradiction, if that was the reason
+    pub count: Option<(T, Count)>,
+    /// How many times generating had to go back to a previous decision
+    pub backtracks: u64
+}
+
+impl<T> Debug for ImpossibleBoardError<T>
+where
+    T: BoardTile
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImpossibleBoardError")
+            .field("coord", &self.coord)
+            .field("constraints", &self.constraints.iter().map(|constraint| constraint.coord).collect::<Vec<_>>())
+            .field("count", &self.count.as_ref().map(|(_, count)| count))
+            .field("backtracks", &self.backtracks)
+            .finish()
+    }
+}
+
+impl<T> std::error::Error for ImpossibleBoardError<T>
+where
+    T: BoardTile
+{}
+
+/// A neighbour that removed possibilities from a slot. See [ImpossibleBoardError]
+pub struct NeighbourConstraint<T>
+where
+    T: BoardTile
+{
+    /// Where the neighbour is, seen from the slot
+    pub direction: T::Direction,
+    /// Position of the neighbour
+    pub coord: (usize, usize, usize),
+    /// The tiles the neighbour could be. Only one if it was decided
+    pub tiles: Vec<T>
+}
 
 #[derive(Debug)]
 /// Returned instead of a coordinate that would be outside the board
 pub struct OutOfBoardError;
 
+impl std::error::Error for OutOfBoardError {}
+
+#[derive(Debug)]
+/// Returned by [Board::set_rule_set()] when the rule set doesn't have the same tiles as the board
+pub struct DifferentTilesError;
+
+impl std::error::Error for DifferentTilesError {}
+
 #[derive(Debug)]
 /// Returned when manually placing tiles that are impossible in the current state of the board.
 /// See [Board::set_tile()]
@@ -221,18 +547,113 @@ pub enum BadPlacementError{
     NotAllPossible
 }
 
+impl std::error::Error for BadPlacementError {}
+
+#[derive(Debug)]
+/// Returned by [Board::set_count()] when the limit is not valid for the board
+pub enum CountError {
+    /// When the tile is not one of the tiles of the board
+    UnknownTile,
+    /// When the minimum of the count is greater than its maximum
+    MinGreaterThanMax,
+    /// When the minimum of the count is greater than the amount of slots of the board
+    NotEnoughSlots
+}
+
+impl std::error::Error for CountError {}
+
+#[derive(Debug)]
+/// Returned by [Board::add_connected_set()] and [Board::add_path()]
+pub enum ConnectionError {
+    /// When one of the tiles is not one of the tiles of the board
+    UnknownTile,
+    /// When one of the ends of the path is outside the board
+    OutOfBoard
+}
+
+impl std::error::Error for ConnectionError {}
+
 /// Represents the 2D or 3D board that we want to procedurally generate.
+///
+/// With the "serde" feature enabled, the board can be saved at any point of the generation
+/// (including its decision tree and random state) and loaded later to continue generating exactly where it stopped.
+/// ```
+#[doc = include_str!("../doc_helpers/MyTile.rs")]
+/// # #[cfg(feature = "serde")] {
+/// let mut board = procedural::Board::<MyTile>::with_seed(5, 5, 1, 42);
+/// board.generate_n(3).unwrap();
+/// let saved = serde_json::to_string(&board).unwrap();
+/// let mut loaded: procedural::Board<MyTile> = serde_json::from_str(&saved).unwrap();
+/// board.generate().unwrap();
+/// loaded.generate().unwrap();
+/// for row in 0..5 {
+///     for col in 0..5 {
+///         assert_eq!(board.get_tile(row, col, 0).unwrap(), loaded.get_tile(row, col, 0).unwrap());
+///     }
+/// }
+/// # }
+/// ```
+/// The [CellSelector] and [TileSelector] are not saved, loaded boards use the default ones. Neither are the [Observer]s.
+///
+/// The board stores its tiles by their position in the tiles of the rules, so loading fails if the tiles have changed since it was saved
+/// (for example, if a variant was added to the enum).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Board<T>
 where
-    T: Tile
+    T: BoardTile
 {
-    tiles: Vec<Vec<Vec<MaybeTile<T>>>>,
-    decision_stack: Vec<DecisionBranch<T>>,
-    dead_ends: Vec<(usize, usize, usize)>,
+    tiles: Vec<Vec<Vec<Slot>>>,
+    /// The decisions that have removed possibilities from each slot, directly or through other slots.
+    /// Going back to any other decision won't give those possibilities back
+    culprits: Vec<Vec<Vec<DecisionSet>>>,
+    decision_stack: Vec<DecisionBranch>,
+    contradiction: Option<Contradiction>,
+    last_contradiction: Option<Contradiction>,
+    backtracks: u64,
+    /// What the board looks like before any decision, if it's not empty. See [Board::regenerate_region()]
+    base: Option<Vec<Vec<Vec<Slot>>>>,
+    /// Every [Board::set_tile()] that succeeded, in order, so they can be placed again after restarting
+    user_placements: Vec<((usize, usize, usize), Slot)>,
+    restart_policy: RestartPolicy,
+    restarts: u32,
+    /// The backtracks there were when the current attempt started
+    restart_backtracks: u64,
+    /// The tiles whose amount is limited. See [Board::set_count()]
+    counts: Vec<TileCount>,
+    /// If any limited tile changed since the limits were last enforced
+    #[cfg_attr(feature = "serde", serde(skip))]
+    counts_changed: bool,
+    /// See [Board::add_connected_set()] and [Board::add_path()]
+    connections: Vec<Connection>,
+    /// If the connections have to be checked from every member, because many slots changed at once
+    connections_unchecked: bool,
     current_layer: usize,
+    /// The undecided slots in the order the [CellSelector] decides them. None until a slot has to be chosen,
+    /// or after changing many slots at once
+    #[cfg_attr(feature = "serde", serde(skip))]
+    candidates: Option<Candidates>,
+    /// Breaks ties between slots with the same score. See [Candidates]
+    tiebreak_seed: u64,
+    /// The edges of the [Graph] the board was created from. If None, neighbours are found with [Direction::neighbour()]
+    edges: Option<Vec<Vec<(usize, usize)>>>,
     width: u32,
     length: u32,
-    height: u32
+    height: u32,
+    rng: ChaCha8Rng,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_cell_selector"))]
+    cell_selector: Box<dyn CellSelector<T>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_tile_selector"))]
+    tile_selector: Box<dyn TileSelector<T>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "Vec::new"))]
+    observers: Vec<Box<dyn Observer<T>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cancelled: bool,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_rules"))]
+    rules: Rules<T>,
+    /// [Rules::fingerprint()] of the tiles the board was created with, the ones its slots refer to.
+    /// Loading a board fails if its tiles are different now
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "check_fingerprint::<T, _>"))]
+    tiles_fingerprint: u64
 }
 
 impl<T> Board<T>
@@ -241,84 +662,709 @@ where
 {
     /// Create an empty board.
     /// The initial state of the board is determined by T::possibles(layer)
+    ///
+    /// The board is seeded randomly, so every board will be different. See [Board::with_seed()] for reproducible boards.
     pub fn new(width: u32, length: u32, height: u32) -> Board<T> {
-        let mut layers = vec![];
-        for cur_layer in 0..height as usize {
-            let mut layer = vec![];
-            for _ in 0..length {
-                let mut row = vec![];
-                for _ in 0..width {
-                    row.push(MaybeTile::Undecided(T::possibles(cur_layer)))
-                }
-                layer.push(row)
+        Board::with_rng(width, length, height, ChaCha8Rng::from_entropy())
+    }
+
+    /// Create an empty board that will always be generated the same way.
+    ///
+    /// The same seed, size, initial state and [Tile] implementation will always produce the same board.
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// let mut board = procedural::Board::<MyTile>::with_seed(5, 5, 1, 42);
+    /// let mut same_board = procedural::Board::<MyTile>::with_seed(5, 5, 1, 42);
+    /// board.generate().unwrap();
+    /// same_board.generate().unwrap();
+    /// for row in 0..5 {
+    ///     for col in 0..5 {
+    ///         assert_eq!(board.get_tile(row, col, 0).unwrap(), same_board.get_tile(row, col, 0).unwrap());
+    ///     }
+    /// }
+    /// ```
+    pub fn with_seed(width: u32, length: u32, height: u32, seed: u64) -> Board<T> {
+        Board::with_rng(width, length, height, ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Create an empty board that chooses which slot to decide next and which tile to place in it with the given selectors.
+    /// See [selection] for the available ones.
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::selection::{Spiral, Uniform};
+    /// // Generates from the center of the board outwards
+    /// let mut board = procedural::Board::<MyTile>::with_selectors(5, 5, 1, Spiral { row: 2, col: 2 }, Uniform);
+    /// board.generate().unwrap();
+    /// ```
+    ///
+    /// The board is seeded randomly. See [Board::with_selectors_and_seed()] for reproducible boards.
+    pub fn with_selectors(
+        width: u32,
+        length: u32,
+        height: u32,
+        cell_selector: impl CellSelector<T> + 'static,
+        tile_selector: impl TileSelector<T> + 'static
+    ) -> Board<T> {
+        let mut board = Board::new(width, length, height);
+        board.set_cell_selector(cell_selector);
+        board.set_tile_selector(tile_selector);
+        board
+    }
+
+    /// Like [Board::with_selectors()], but the board will always be generated the same way. See [Board::with_seed()]
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::selection::{Spiral, Uniform};
+    /// let mut board = procedural::Board::<MyTile>::with_selectors_and_seed(5, 5, 1, Spiral { row: 2, col: 2 }, Uniform, 42);
+    /// let mut same_board = procedural::Board::<MyTile>::with_selectors_and_seed(5, 5, 1, Spiral { row: 2, col: 2 }, Uniform, 42);
+    /// board.generate().unwrap();
+    /// same_board.generate().unwrap();
+    /// for row in 0..5 {
+    ///     for col in 0..5 {
+    ///         assert_eq!(board.get_tile(row, col, 0).unwrap(), same_board.get_tile(row, col, 0).unwrap());
+    ///     }
+    /// }
+    /// ```
+    pub fn with_selectors_and_seed(
+        width: u32,
+        length: u32,
+        height: u32,
+        cell_selector: impl CellSelector<T> + 'static,
+        tile_selector: impl TileSelector<T> + 'static,
+        seed: u64
+    ) -> Board<T> {
+        let mut board = Board::with_seed(width, length, height, seed);
+        board.set_cell_selector(cell_selector);
+        board.set_tile_selector(tile_selector);
+        board
+    }
+
+    /// Create an empty board with a slot for every node of `graph`. Node `n` is at row 0, column `n` and layer 0.
+    /// See [Graph]
+    ///
+    /// The board is seeded randomly. See [Board::from_graph_with_seed()] for reproducible boards.
+    pub fn from_graph(graph: &Graph<T::Direction>) -> Board<T>
+    where
+        T::Direction: PartialEq
+    {
+        let mut board = Board::new(graph.nodes() as u32, 1, 1);
+        board.edges = Some(graph.edges.clone());
+        board
+    }
+
+    /// Like [Board::from_graph()], but the board will always be generated the same way. See [Board::with_seed()]
+    pub fn from_graph_with_seed(graph: &Graph<T::Direction>, seed: u64) -> Board<T>
+    where
+        T::Direction: PartialEq
+    {
+        let mut board = Board::with_seed(graph.nodes() as u32, 1, 1, seed);
+        board.edges = Some(graph.edges.clone());
+        board
+    }
+
+    fn with_rng(width: u32, length: u32, height: u32, rng: ChaCha8Rng) -> Board<T> {
+        #[cfg(feature = "validate")]
+        {
+            let report = validate_with_size::<T>(width, length, height);
+            if !report.is_ok() {
+                print!("{report}")
             }
-            layers.push(layer)
         }
 
-        let board = Board {
-            tiles: layers,
+        Board::with_rules(width, length, height, Rules::new(), rng)
+    }
+}
+
+impl<T> Board<T>
+where
+    T: BoardTile
+{
+    /// Create an empty board that follows `rules` instead of the ones of [Tile]. See [RuleSet]
+    ///
+    /// The board is seeded randomly. See [Board::with_rule_set_and_seed()] for reproducible boards.
+    pub fn with_rule_set(width: u32, length: u32, height: u32, rules: RuleSet<T>) -> Board<T> {
+        Board::with_rules(width, length, height, rules.rules, ChaCha8Rng::from_entropy())
+    }
+
+    /// Like [Board::with_rule_set()], but the board will always be generated the same way. See [Board::with_seed()]
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::{Board, RuleSet};
+    /// let sample = vec![vec![
+    ///     vec![MyTile::Green, MyTile::Yellow, MyTile::Green],
+    ///     vec![MyTile::Yellow, MyTile::Green, MyTile::Yellow],
+    /// ]];
+    /// let rules = RuleSet::learn(&sample);
+    /// let mut board = Board::with_rule_set_and_seed(5, 5, 1, rules.clone(), 42);
+    /// let mut same_board = Board::with_rule_set_and_seed(5, 5, 1, rules, 42);
+    /// board.generate().unwrap();
+    /// same_board.generate().unwrap();
+    /// for row in 0..5 {
+    ///     for col in 0..5 {
+    ///         assert_eq!(board.get_tile(row, col, 0).unwrap(), same_board.get_tile(row, col, 0).unwrap());
+    ///     }
+    /// }
+    /// ```
+    pub fn with_rule_set_and_seed(width: u32, length: u32, height: u32, rules: RuleSet<T>, seed: u64) -> Board<T> {
+        Board::with_rules(width, length, height, rules.rules, ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Changes the rules followed by the board. See [RuleSet]
+    ///
+    /// The rules aren't saved with the board, so a board created with [Board::with_rule_set()] must be given
+    /// the same rule set again after loading it. Rule sets with other tiles (or the same tiles in another order) are rejected,
+    /// since the board wouldn't know which tile is in each slot.
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::{Board, RuleSet};
+    /// let sample = vec![vec![vec![MyTile::Green, MyTile::Yellow]]];
+    /// let mut board = Board::with_rule_set(5, 5, 1, RuleSet::learn(&sample));
+    /// board.set_rule_set(RuleSet::learn(&sample)).unwrap();
+    /// let with_red = vec![vec![vec![MyTile::Green, MyTile::Yellow, MyTile::Red]]];
+    /// assert!(board.set_rule_set(RuleSet::learn(&with_red)).is_err());
+    /// ```
+    pub fn set_rule_set(&mut self, rules: RuleSet<T>) -> Result<(), DifferentTilesError> {
+        if rules.rules.fingerprint() != self.tiles_fingerprint {
+            return Err(DifferentTilesError)
+        }
+        self.rules = rules.rules;
+        Ok(())
+    }
+
+    fn with_rules(width: u32, length: u32, height: u32, rules: Rules<T>, mut rng: ChaCha8Rng) -> Board<T> {
+        let tiebreak_seed = rng.gen();
+        let mut board = Board {
+            tiles: vec![],
+            culprits: vec![],
             decision_stack: vec![],
-            dead_ends: vec![],
+            contradiction: None,
+            last_contradiction: None,
+            backtracks: 0,
+            base: None,
+            user_placements: vec![],
+            restart_policy: RestartPolicy::Never,
+            restarts: 0,
+            restart_backtracks: 0,
+            counts: vec![],
+            counts_changed: false,
+            connections: vec![],
+            connections_unchecked: false,
+            edges: None,
             width,
             length,
             height,
             current_layer: 0,
+            candidates: None,
+            tiebreak_seed,
+            rng,
+            cell_selector: default_cell_selector(),
+            tile_selector: default_tile_selector(),
+            observers: vec![],
+            cancelled: false,
+            tiles_fingerprint: rules.fingerprint(),
+            rules
         };
+        board.tiles = board.empty_tiles();
+        board.culprits = board.empty_culprits();
+        board
+    }
 
-        #[cfg(feature = "validate")]
-        board.validate();
+    fn empty_tiles(&self) -> Vec<Vec<Vec<Slot>>> {
+        (0..self.height as usize).map(|layer| {
+            let possibles = Slot::Undecided(self.rules.possibles(layer));
+            vec![vec![possibles; self.width as usize]; self.length as usize]
+        }).collect()
+    }
 
-        board
+    fn empty_culprits(&self) -> Vec<Vec<Vec<DecisionSet>>> {
+        vec![vec![vec![DecisionSet::new(); self.width as usize]; self.length as usize]; self.height as usize]
     }
 
-    #[cfg(feature = "validate")]
-    fn validate(&self) {
-        let all = T::all();
-        let mut names = HashSet::new();
-        all.iter().for_each(|tile| {
-            if !names.insert(tile.get_name()) {
-                println!("WARNING: There is more than one tile with the name `{}`", tile.get_name())
-            }
-        });
-        let directions = T::Direction::all();
-        all.iter()
-            .flat_map(|tile| all.iter().map(|t| (tile, t)).collect::<Vec<_>>())
-            .flat_map(|(tile1, tile2)| directions.iter().map(|direction| (tile1, tile2, direction)).collect::<Vec<_>>())
-            .for_each(|(tile1, tile2, direction)| {
-                if tile1.get_rules()(tile2, *direction)
-                    !=
-                    tile2.get_rules()(tile1, direction.opposite())
-                {
-                    println!("ERROR: Rules are not bidirectional for {tile1:?} {tile2:?} {direction:?}")
-                }
-            });
-        // TODO: Check if all directions have an opposite. And that that opposite truly is the opposite.
+    /// Changes the seed used for the rest of the generation. See [Board::with_seed()]
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    /// Changes how the next slot to decide is chosen. By default it's [selection::MinPossibilities]
+    pub fn set_cell_selector(&mut self, cell_selector: impl CellSelector<T> + 'static) {
+        self.cell_selector = Box::new(cell_selector);
+        // The scores are different now
+        self.candidates = None;
+    }
+
+    /// Changes how the tile placed in a slot is chosen. By default it's [selection::Distribution]
+    pub fn set_tile_selector(&mut self, tile_selector: impl TileSelector<T> + 'static) {
+        self.tile_selector = Box::new(tile_selector);
+    }
+
+    /// Changes when generating gives up and starts over. By default it never does. See [RestartPolicy]
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::{MaybeTile, RestartPolicy};
+    /// let mut board = procedural::Board::<MyTile>::new(10, 10, 1);
+    /// board.set_restart_policy(RestartPolicy::Luby { unit: 100 });
+    /// board.set_tile(MaybeTile::Decided(MyTile::Red), 5, 5, 0).unwrap();
+    /// board.generate().unwrap();
+    /// // Even if it restarted, the tiles placed by hand are still there
+    /// assert_eq!(board.get_tile(5, 5, 0).unwrap(), MaybeTile::Decided(MyTile::Red));
+    /// ```
+    ///
+    /// # Panics
+    /// If `unit` or `first` are 0, or `factor` is not greater than 1
+    pub fn set_restart_policy(&mut self, restart_policy: RestartPolicy) {
+        match restart_policy {
+            RestartPolicy::Never => (),
+            RestartPolicy::Luby { unit } => assert!(unit > 0, "the unit of the restart policy must be greater than 0"),
+            RestartPolicy::Geometric { first, factor } => {
+                assert!(first > 0, "the first attempt of the restart policy must be greater than 0");
+                assert!(factor > 1.0, "the factor of the restart policy must be greater than 1");
+            },
+        }
+        self.restart_policy = restart_policy;
+    }
+
+    /// How many times generating has started over since the board was created or cleaned. See [RestartPolicy]
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Limits how many slots of the whole board can have `tile`, replacing any previous limit it had. See [Count]
+    ///
+    /// Once a tile reaches its maximum it's removed from every other slot, once it needs every slot it can still be in
+    /// to reach its minimum it's placed in all of them, and a board that doesn't have enough slots left
+    /// for a tile to reach its minimum goes back to a previous decision.
+    /// It's meant to be called before generating, and the limits are kept after [Board::clean()].
+    /// Tight limits can make generating go back many times, see [RestartPolicy].
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::{Count, MaybeTile};
+    /// let mut board = procedural::Board::<MyTile>::new(10, 10, 1);
+    /// board.set_count(MyTile::Red, Count::exactly(1)).unwrap();
+    /// board.set_count(MyTile::Yellow, Count::at_most(5)).unwrap();
+    /// // At least 20% of the board
+    /// board.set_count(MyTile::Green, Count::at_least(10 * 10 / 5)).unwrap();
+    /// board.generate().unwrap();
+    /// let count = |tile| (0..10).flat_map(|row| (0..10).map(move |col| (row, col)))
+    ///     .filter(|&(row, col)| board.get_tile(row, col, 0).unwrap() == MaybeTile::Decided(tile))
+    ///     .count();
+    /// assert_eq!(count(MyTile::Red), 1);
+    /// assert!(count(MyTile::Yellow) <= 5);
+    /// assert!(count(MyTile::Green) >= 20);
+    /// ```
+    ///
+    /// Fails with [CountError] if `tile` is not one of the tiles of the board, if the minimum is greater than the maximum
+    /// or if the minimum is greater than the amount of slots of the board. The previous limit is kept then.
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::{Count, CountError};
+    /// let mut board = procedural::Board::<MyTile>::new(2, 2, 1);
+    /// assert!(matches!(board.set_count(MyTile::Red, Count { min: 3, max: 2 }), Err(CountError::MinGreaterThanMax)));
+    /// assert!(matches!(board.set_count(MyTile::Red, Count::at_least(5)), Err(CountError::NotEnoughSlots)));
+    /// assert!(board.set_count(MyTile::Red, Count::exactly(4)).is_ok());
+    /// ```
+    ///
+    /// When the limits can't all be met, [ImpossibleBoardError::count] says which one was broken last.
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use procedural::{Count, MaybeTile};
+    /// let mut board = procedural::Board::<MyTile>::new(2, 2, 1);
+    /// board.set_tile(MaybeTile::Decided(MyTile::Green), 0, 0, 0).unwrap();
+    /// // Only 3 slots are left for red
+    /// board.set_count(MyTile::Red, Count::exactly(4)).unwrap();
+    /// let error = board.generate().unwrap_err();
+    /// assert_eq!(error.count, Some((MyTile::Red, Count::exactly(4))));
+    /// assert!(error.to_string().contains("couldn't be in exactly 4 slots"));
+    /// ```
+    pub fn set_count(&mut self, tile: T, count: Count) -> Result<(), CountError> {
+        let tile = self.rules.index(&tile).ok_or(CountError::UnknownTile)?;
+        if count.min > count.max {
+            return Err(CountError::MinGreaterThanMax)
+        }
+        if count.min > self.coords().count() {
+            return Err(CountError::NotEnoughSlots)
+        }
+        self.counts.retain(|limited| limited.tile != tile);
+        self.counts.push(TileCount::new(tile, count));
+        self.recount();
+        Ok(())
+    }
+
+    /// Updates what is kept about the slots after changing many of them at once
+    fn slots_replaced(&mut self) {
+        self.recount();
+        self.reconnect();
+        self.candidates = None;
+    }
+
+    /// Recomputes the amount and culprits of every limited tile
+    fn recount(&mut self) {
+        for tile_count in &mut self.counts {
+            *tile_count = TileCount::new(tile_count.tile, tile_count.count);
+        }
+        for index in 0..self.coords().count() {
+            let (row, col, layer) = self.coord(index);
+            let slot = &self.tiles[layer][row][col];
+            for tile_count in &mut self.counts {
+                tile_count.change(index, &Slot::Undecided(TileSet::new()), slot);
+                tile_count.blame(slot, &self.culprits[layer][row][col]);
+            }
+        }
+        self.counts_changed = true;
+    }
+
+    /// Recomputes the members of every connection, and has them checked from every member
+    fn reconnect(&mut self) {
+        for i in 0..self.connections.len() {
+            let mut members = match self.connections[i].ends {
+                Some(_) => vec![false; (self.width * self.length * self.height) as usize],
+                None => self.tiles.iter().flatten().flatten().map(|slot| slot.only(&self.connections[i].tiles)).collect(),
+            };
+            if let Some((from, to)) = self.connections[i].ends {
+                members[from] = true;
+                members[to] = true;
+            }
+            let connection = &mut self.connections[i];
+            connection.total = members.iter().filter(|member| **member).count();
+            connection.members = members;
+            connection.cut = vec![];
+            connection.joined = vec![];
+        }
+        self.connections_unchecked = true;
+    }
+
+    /// Makes the slots with any of `tiles` form a single group, where every slot of the group can be reached from any other
+    /// going only through slots of the group. Slots are next to each other if they are neighbours in the board,
+    /// in any of the directions of the tile (or edges of the [Graph]).
+    ///
+    /// A board where the group has been split goes back to a previous decision. Calling it again adds another group,
+    /// and the groups are kept after [Board::clean()]. It's meant to be called before generating.
+    /// ```
+    #[doc = include_str!("../doc_helpers/LandTile.rs")]
+    /// // The water is a single lake
+    /// let mut board = Board::<Land>::with_seed(8, 8, 1, 42);
+    /// board.add_connected_set([Land::Water]).unwrap();
+    /// board.generate().unwrap();
+    /// let water = (0..8).flat_map(|row| (0..8).map(move |col| (row, col)))
+    ///     .filter(|&(row, col)| board.get_tile(row, col, 0).unwrap() == MaybeTile::Decided(Land::Water))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(flood(&board, water[0]).len(), water.len());
+    /// ```
+    ///
+    /// Returns [ConnectionError::UnknownTile] if any of `tiles` is not one of the tiles of the board.
+    pub fn add_connected_set(&mut self, tiles: impl IntoIterator<Item = T>) -> Result<(), ConnectionError> {
+        let tiles = self.tile_set(tiles).ok_or(ConnectionError::UnknownTile)?;
+        self.connections.push(Connection::new(tiles, None));
+        self.reconnect();
+        Ok(())
+    }
+
+    /// Makes `from` and `to`, as (row, col, layer), be joined by a path of neighbouring slots with any of `tiles`,
+    /// both included. See [Board::add_connected_set()] for when slots are next to each other.
+    ///
+    /// Returns [ConnectionError] if `from` or `to` is outside the board, or if any of `tiles` is not one of the tiles of the board.
+    /// A board where there can't be such a path goes back to a previous decision, and the paths are kept after [Board::clean()]. It's meant to be called before generating.
+    /// ```
+    #[doc = include_str!("../doc_helpers/LandTile.rs")]
+    /// // The top-left and bottom-right corners are joined by water
+    /// let mut board = Board::<Land>::with_seed(8, 8, 1, 42);
+    /// board.add_path((0, 0, 0), (7, 7, 0), [Land::Water]).unwrap();
+    /// board.generate().unwrap();
+    /// assert!(flood(&board, (0, 0)).contains(&(7, 7)));
+    /// ```
+    pub fn add_path(
+        &mut self,
+        from: (usize, usize, usize),
+        to: (usize, usize, usize),
+        tiles: impl IntoIterator<Item = T>
+    ) -> Result<(), ConnectionError> {
+        self.get_slot(from.0, from.1, from.2).map_err(|_| ConnectionError::OutOfBoard)?;
+        self.get_slot(to.0, to.1, to.2).map_err(|_| ConnectionError::OutOfBoard)?;
+        let tiles = self.tile_set(tiles).ok_or(ConnectionError::UnknownTile)?;
+        self.connections.push(Connection::new(tiles, Some((self.index(from), self.index(to)))));
+        self.reconnect();
+        Ok(())
+    }
+
+    /// The indices of `tiles` in [Rules]. None if any of them is not one of the tiles of the board
+    fn tile_set(&self, tiles: impl IntoIterator<Item = T>) -> Option<TileSet> {
+        tiles.into_iter().map(|tile| self.rules.index(&tile)).collect()
+    }
+
+    /// Adds an observer that will be notified of every [observer::Event] while generating. See [Observer]
+    pub fn add_observer(&mut self, observer: impl Observer<T> + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// If an [Observer] stopped the last call to [Board::generate()], [Board::generate_n()] or [Board::generate_with_budget()]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    fn notify(&mut self, event: Event<T>) {
+        for observer in self.observers.iter_mut() {
+            if let Control::Cancel = observer.notify(&event) {
+                self.cancelled = true
+            }
+        }
     }
 
     /// Returns the board to its empty state. Exactly the same as Board::new(), except wihout creating a new object
+    ///
+    /// The random number generator is not reset, so generating again will give a different board.
     pub fn clean(&mut self) {
-        self.tiles = vec![];
-        for cur_layer in 0..self.height as usize {
-            let mut layer = vec![];
-            for _ in 0..self.length {
-                let mut row = vec![];
-                for _ in 0..self.width {
-                    row.push(MaybeTile::Undecided(T::possibles(cur_layer)))
+        self.tiles = self.empty_tiles();
+        self.culprits = self.empty_culprits();
+        self.decision_stack = vec![];
+        self.contradiction = None;
+        self.last_contradiction = None;
+        self.backtracks = 0;
+        self.base = None;
+        self.user_placements = vec![];
+        self.restarts = 0;
+        self.restart_backtracks = 0;
+        self.current_layer = 0;
+        self.cancelled = false;
+        self.tiebreak_seed = self.rng.gen();
+        self.slots_replaced();
+    }
+
+    /// Empties the board except for the tiles placed with [Board::set_tile()] and the ones outside the region being regenerated,
+    /// and continues with a different random state
+    fn restart(&mut self) -> Result<(), ImpossibleBoardError<T>> {
+        self.tiles = match &self.base {
+            Some(base) => base.clone(),
+            None => self.empty_tiles(),
+        };
+        self.slots_replaced();
+        self.culprits = self.empty_culprits();
+        self.decision_stack = vec![];
+        self.contradiction = None;
+        self.current_layer = 0;
+        self.restarts += 1;
+        self.restart_backtracks = self.backtracks;
+        let seed = self.rng.gen();
+        self.reseed(seed);
+        self.tiebreak_seed = self.rng.gen();
+        if let Err(contradiction) = self.place_again() {
+            // Tiles placed by hand are never undone, so there is nothing to go back to
+            self.set_contradiction(contradiction);
+            return Err(self.impossible())
+        }
+        self.notify(Event::Restarted);
+        Ok(())
+    }
+
+    /// Repeats every [Board::set_tile()] that succeeded before. The ones that are still on the board don't change anything
+    fn place_again(&mut self) -> Result<(), Contradiction> {
+        for i in 0..self.user_placements.len() {
+            let (coord, slot) = self.user_placements[i].clone();
+            self.place(coord, &slot)?;
+        }
+        Ok(())
+    }
+
+    /// Leaves in the slot at `coord` only the tiles of `slot` and propagates it.
+    ///
+    /// It's not a decision, so it's not a culprit of anything: the changes are kept in the current branch,
+    /// and going back past it undoes them until they are placed again. See [Board::place_again()]
+    fn place(&mut self, coord: (usize, usize, usize), slot: &Slot) -> Result<(), Contradiction> {
+        let (row, col, layer) = coord;
+        let current = &self.tiles[layer][row][col];
+        let possibilities = slot.iter().filter(|tile| current.contains(*tile)).collect::<TileSet>();
+        if possibilities.len() == current.len() {
+            return Ok(())
+        }
+        let culprits = self.culprits[layer][row][col].clone();
+        self.reduce(coord, possibilities, &culprits)?;
+        self.propagate(VecDeque::from([coord]))
+    }
+
+    fn should_restart(&self) -> bool {
+        match self.restart_policy.limit(self.restarts) {
+            Some(limit) => self.backtracks - self.restart_backtracks >= limit,
+            None => false,
+        }
+    }
+
+    /// Generates again the slots between `min` and `max` (both included), as (row, col, layer),
+    /// leaving the rest of the board as it is. `max` is clamped to the size of the board.
+    ///
+    /// The slots in the region start with the possibilities allowed by the slots around it,
+    /// and the tiles placed before with [Board::set_tile()] inside the region are forgotten.
+    /// It's meant for boards that are already complete, otherwise the slots outside the region that are not decided will be generated too.
+    /// If the region can't be generated, the board is left as it was, including [Board::backtracks()] and [Board::restarts()].
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// let mut board = procedural::Board::<MyTile>::new(10, 10, 1);
+    /// board.generate().unwrap();
+    /// let before = board.get_tile(0, 0, 0).unwrap();
+    /// // Change the bottom-right corner
+    /// board.regenerate_region((5, 5, 0), (9, 9, 0)).unwrap();
+    /// assert_eq!(board.get_tile(0, 0, 0).unwrap(), before);
+    /// ```
+    ///
+    /// ```
+    #[doc = include_str!("../doc_helpers/RoomTile.rs")]
+    /// use procedural::{Count, RestartPolicy};
+    /// let mut board = rooms();
+    /// board.set_restart_policy(RestartPolicy::Luby { unit: 1 });
+    /// board.generate().unwrap();
+    /// let (backtracks, restarts) = (board.backtracks(), board.restarts());
+    /// // Without green, the three rooms with walls between them can't all be different
+    /// board.set_count(Color::Green, Count::at_most(0)).unwrap();
+    /// let error = board.regenerate_region((0, 0, 0), (0, 4, 0)).unwrap_err();
+    /// assert!(error.backtracks > backtracks);
+    /// assert_eq!(board.get_tile(0, 0, 0).unwrap(), MaybeTile::Decided(Color::Green));
+    /// assert_eq!(board.backtracks(), backtracks);
+    /// assert_eq!(board.restarts(), restarts);
+    /// ```
+    pub fn regenerate_region(&mut self, min: (usize, usize, usize), max: (usize, usize, usize)) -> Result<(), ImpossibleBoardError<T>> {
+        let tiles = self.tiles.clone();
+        let culprits = std::mem::take(&mut self.culprits);
+        let decision_stack = std::mem::take(&mut self.decision_stack);
+        let contradiction = self.contradiction.clone();
+        let last_contradiction = self.last_contradiction.clone();
+        let base = self.base.clone();
+        let user_placements = self.user_placements.clone();
+        let current_layer = self.current_layer;
+        let (backtracks, restarts, restart_backtracks) = (self.backtracks, self.restarts, self.restart_backtracks);
+        let regenerated = self.regenerate(min, max);
+        if regenerated.is_err() {
+            self.tiles = tiles;
+            self.culprits = culprits;
+            self.decision_stack = decision_stack;
+            self.contradiction = contradiction;
+            self.last_contradiction = last_contradiction;
+            self.base = base;
+            self.user_placements = user_placements;
+            self.current_layer = current_layer;
+            self.backtracks = backtracks;
+            self.restarts = restarts;
+            self.restart_backtracks = restart_backtracks;
+            self.slots_replaced();
+        }
+        regenerated
+    }
+
+    fn regenerate(&mut self, min: (usize, usize, usize), max: (usize, usize, usize)) -> Result<(), ImpossibleBoardError<T>> {
+        let max = (
+            max.0.min(self.length as usize - 1),
+            max.1.min(self.width as usize - 1),
+            max.2.min(self.height as usize - 1)
+        );
+        let inside = |(row, col, layer): (usize, usize, usize)| {
+            (min.0..=max.0).contains(&row) && (min.1..=max.1).contains(&col) && (min.2..=max.2).contains(&layer)
+        };
+        self.decision_stack = vec![];
+        self.culprits = self.empty_culprits();
+        self.contradiction = None;
+        self.last_contradiction = None;
+        self.user_placements.retain(|(coord, _)| !inside(*coord));
+
+        let mut region = vec![];
+        for layer in min.2..=max.2 {
+            for row in min.0..=max.0 {
+                for col in min.1..=max.1 {
+                    self.tiles[layer][row][col] = Slot::Undecided(self.rules.possibles(layer));
+                    region.push((row, col, layer));
                 }
-                layer.push(row)
             }
-            self.tiles.push(layer)
         }
-        self.decision_stack = vec![];
-        self.dead_ends = vec![];
-        self.current_layer = 0;
+        self.slots_replaced();
+        // The slots around the region remove the possibilities they don't allow
+        let mut around = HashSet::new();
+        for coord in region {
+            for i in 0..self.degree(coord) {
+                if let Some((_, neighbour)) = self.neighbour(coord, i) {
+                    if !inside(neighbour) {
+                        around.insert(neighbour);
+                    }
+                }
+            }
+        }
+        if let Err(contradiction) = self.propagate(ordered_coords(around)) {
+            self.last_contradiction = Some(contradiction.clone());
+            self.contradiction = Some(contradiction);
+            return Err(self.impossible())
+        }
+        self.base = Some(self.tiles.clone());
+        self.current_layer = min.2;
+        self.generate()
     }
 
     /// Fill the entire board.
     /// Either it retuns Ok(()) and the board is full, or the board is impossible to fill. See [ImpossibleBoardError]
-    pub fn generate(&mut self) -> Result<(), ImpossibleBoardError> {
+    ///
+    /// If an [Observer] cancels the generation, it returns Ok(()) early and [Board::is_cancelled()] is true.
+    /// A slot that can't have any tile, like the ones of a layer where [Tile::possibles()] is empty, makes the board impossible.
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use procedural::directions::Direction6;
+    /// # #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    /// # struct Ground;
+    /// # impl procedural::Tile for Ground {
+    /// #     type Direction = Direction6;
+    /// #     fn all() -> HashSet<Self> { HashSet::from([Ground]) }
+    /// // Nothing can be placed above the ground
+    /// fn possibles(layer: usize) -> HashSet<Self> {
+    ///     match layer {
+    ///         0 => HashSet::from([Ground]),
+    ///         _ => HashSet::new(),
+    ///     }
+    /// }
+    /// #     fn get_rules(&self) -> Box<dyn Fn(&Self, Direction6) -> bool + '_> { Box::new(|_: &Self, _: Direction6| true) }
+    /// #     fn get_distribution(&self, _layer: usize) -> u32 { 1 }
+    /// # }
+    /// let mut board = procedural::Board::<Ground>::new(3, 3, 2);
+    /// let error = board.generate().unwrap_err();
+    /// assert_eq!(error.coord.map(|(_, _, layer)| layer), Some(1));
+    /// ```
+    ///
+    /// When a decision leads to a dead end, generating goes back straight to the decisions that caused it,
+    /// without trying other tiles in the slots decided in between that had nothing to do with it.
+    /// Tiles placed with [Board::set_tile()] in between don't stop it either.
+    /// ```
+    #[doc = include_str!("../doc_helpers/RoomTile.rs")]
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// use procedural::observer::{Control, Event};
+    /// let mut board = rooms();
+    /// let room_1 = Rc::new(RefCell::new(vec![]));
+    /// let placed = room_1.clone();
+    /// board.add_observer(move |event: &Event<Color>| {
+    ///     if let Event::Collapsed { col: 1, tile, .. } = event {
+    ///         placed.borrow_mut().push(*tile);
+    ///     }
+    ///     Control::Continue
+    /// });
+    /// board.generate().unwrap();
+    /// // Room 0 can't be red
+    /// assert_eq!(board.get_tile(0, 0, 0).unwrap(), MaybeTile::Decided(Color::Green));
+    /// // Room 1 was decided before finding that out, but never painted another color
+    /// assert!(room_1.borrow().iter().all(|tile| *tile == Color::Red));
+    /// assert!(board.backtracks() > 0);
+    ///
+    /// let mut board = rooms();
+    /// let backtracked = Rc::new(RefCell::new(vec![]));
+    /// let rooms_backtracked = backtracked.clone();
+    /// board.add_observer(move |event: &Event<Color>| {
+    ///     if let Event::Backtracked { col, .. } = event {
+    ///         rooms_backtracked.borrow_mut().push(*col);
+    ///     }
+    ///     Control::Continue
+    /// });
+    /// // Paints room 0 red, and then room 1 by hand
+    /// board.generate_n(1).unwrap();
+    /// board.set_tile(MaybeTile::Decided(Color::Blue), 0, 1, 0).unwrap();
+    /// board.generate().unwrap();
+    /// assert!(backtracked.borrow().contains(&0));
+    /// assert!(!backtracked.borrow().contains(&1));
+    /// assert_eq!(board.get_tile(0, 0, 0).unwrap(), MaybeTile::Decided(Color::Green));
+    /// ```
+    pub fn generate(&mut self) -> Result<(), ImpossibleBoardError<T>> {
+        self.cancelled = false;
         let mut complete = false;
-        while !complete {
+        while !complete && !self.cancelled {
             complete = self.generate_1()?;
         };
         Ok(())
@@ -326,54 +1372,102 @@ where
 
     /// Generate a single tile. It may generate more than one if the generated tile makes it so that only 1 tile can be in another position.
     /// returns true if the board has been filled. False if not. [ImpossibleBoardError] if it can't continue.
-    pub fn generate_1(&mut self) -> Result<bool, ImpossibleBoardError> {
+    pub fn generate_1(&mut self) -> Result<bool, ImpossibleBoardError<T>> {
         match self.get_status() {
-            BranchStatus::Complete => Ok(true),
-            BranchStatus::DeadEnd => {self.go_back()?;Ok(false)},
+            BranchStatus::Complete => {self.notify(Event::Finished); Ok(true)},
+            BranchStatus::DeadEnd => {
+                if self.should_restart() {
+                    self.restart()?
+                } else {
+                    self.go_back()?
+                }
+                Ok(false)
+            },
             BranchStatus::Incomplete => {
-                let (tile, row, col, layer) = if self.can_continue_branch() {
-                    if let Some(current_branch) = self.decision_stack.last_mut() {
-                        let (row, col, layer) = current_branch.deciding_coord;
-                        let mut rng = rand::thread_rng();
-                        let mut weights = Vec::new(); // TODO: Take weights into account
-                        let options = match &self.tiles[layer][row][col] {
-                            MaybeTile::Undecided(possibilities) => possibilities
-                                .iter()
-                                .filter(|tile| !current_branch.tried_tiles.contains(tile))
-                                .map(|tile| {
-                                    weights.push(tile.get_distribution(layer));
-                                    tile
-                                })
-                                .collect::<Vec<_>>(),
-                            MaybeTile::Decided(_) => unreachable!(),
-                        };
-                        let dist = WeightedIndex::new(&weights).unwrap();
-                        let choice = *options[dist.sample(&mut rng)];
-                        current_branch.tried_tiles.insert(choice);
-                        (choice, row, col, layer)
-                    } else {
-                        unreachable!()
+                let (row, col, layer) = if self.can_continue_branch() {
+                    match self.decision_stack.last() {
+                        Some(current_branch) => current_branch.deciding_coord,
+                        None => unreachable!(),
                     }
                 } else {
-                    let (row, col, layer) = self.get_undecided();
-                    let tile = self.make_decision(row, col, layer);
+                    let (row, col, layer) = match self.get_undecided() {
+                        Ok(coord) => coord,
+                        Err(contradiction) => {
+                            self.set_contradiction(contradiction);
+                            return Ok(false)
+                        },
+                    };
                     let new_branch = DecisionBranch::new(row, col, layer);
                     self.decision_stack.push(new_branch);
-                    (tile, row, col, layer)
+                    self.notify(Event::BranchPushed { row, col, layer });
+                    (row, col, layer)
+                };
+                let tile = match self.make_decision(row, col, layer) {
+                    Some(tile) => tile,
+                    None => {
+                        // Every tile of the slot has been tried already
+                        let mut culprits = self.culprits[layer][row][col].clone();
+                        if let Some(current_branch) = self.decision_stack.last() {
+                            culprits.union_with(&current_branch.conflicts);
+                        }
+                        let contradiction = self.contradiction_at((row, col, layer), culprits);
+                        self.set_contradiction(contradiction);
+                        return Ok(false)
+                    },
                 };
-                self.change_tile(tile, row, col, layer);
+                if let Err(contradiction) = self.change_tile(tile, row, col, layer) {
+                    self.set_contradiction(contradiction)
+                }
                 Ok(false)
             }
-            BranchStatus::CompleteLayer => {self.current_layer += 1; Ok(false)},
+            BranchStatus::CompleteLayer => {
+                self.notify(Event::LayerCompleted { layer: self.current_layer });
+                self.current_layer += 1;
+                Ok(false)
+            },
+        }
+    }
+
+    /// Makes generating go back from `contradiction` the next time
+    fn set_contradiction(&mut self, contradiction: Contradiction) {
+        self.last_contradiction = Some(contradiction.clone());
+        self.contradiction = Some(contradiction)
+    }
+
+    fn change_tile(&mut self, tile: usize, row: usize, col: usize, layer: usize) -> Result<(), Contradiction> {
+        self.set_slot((row, col, layer), Slot::Decided(tile), &self.current_decision());
+        self.decision_stack.last_mut().unwrap().tried_tiles.insert(tile);
+        self.notify(Event::Collapsed { row, col, layer, tile: self.rules.tile(tile) });
+        self.propagate(VecDeque::from([(row, col, layer)]))
+    }
+
+    /// Changes a slot and adds `culprits` to the decisions that led to it,
+    /// remembering its previous state in the current branch so it can be undone
+    fn set_slot(&mut self, (row, col, layer): (usize, usize, usize), slot: Slot, culprits: &DecisionSet) {
+        let previous = std::mem::replace(&mut self.tiles[layer][row][col], slot);
+        self.update_candidate((row, col, layer), &previous);
+        let previous_culprits = self.culprits[layer][row][col].clone();
+        self.culprits[layer][row][col].union_with(culprits);
+        let index = self.index((row, col, layer));
+        let (slot, culprits) = (&self.tiles[layer][row][col], &self.culprits[layer][row][col]);
+        for connection in &mut self.connections {
+            connection.change(index, &previous, slot);
+        }
+        for (i, tile_count) in self.counts.iter_mut().enumerate() {
+            self.counts_changed |= tile_count.change(index, &previous, slot);
+            let added = tile_count.blame(slot, culprits);
+            if let (Some(current_branch), Some((decided, added))) = (self.decision_stack.last_mut(), added) {
+                current_branch.count_trail.push((i, decided, added))
+            }
+        }
+        if let Some(current_branch) = self.decision_stack.last_mut() {
+            current_branch.trail.push(((row, col, layer), previous, previous_culprits))
         }
     }
 
-    fn change_tile(&mut self, tile: T, row: usize, col: usize, layer: usize) {
-        self.tiles[layer][row][col] = MaybeTile::Decided(tile);
-        let new_propagated = self.propagate(tile, row, col, layer);
-        let current_branch = self.decision_stack.last_mut().unwrap();
-        current_branch.tried_tiles.insert(tile);
-        current_branch.temp_decided_coords.extend(new_propagated.into_iter());
+    /// The decision being made right now, as a set of culprits
+    fn current_decision(&self) -> DecisionSet {
+        DecisionSet::from_iter([self.decision_stack.len() - 1])
     }
 
     /// Tries to set a tile. This can only be done to reduce possibilities, otherwise it will return [BadPlacementError].
@@ -412,64 +1506,108 @@ where
     /// board.set_tile(procedural::MaybeTile::Undecided(yellow_and_red), 0, 0, 0).unwrap(); // This will panic.
     /// // Since 0,0,0 doesn't have red as a possibility (due to the previous iine), and we tried to add it.
     /// ```
+    ///
+    /// Tiles can also be placed after generating part of the board. Generating never changes them,
+    /// but it can still change the decisions it made before they were placed.
+    /// ```
+    #[doc = include_str!("../doc_helpers/RoomTile.rs")]
+    /// let mut board = rooms();
+    /// // Paints room 0 red
+    /// board.generate_n(1).unwrap();
+    /// board.set_tile(MaybeTile::Decided(Color::Blue), 0, 1, 0).unwrap();
+    /// board.generate().unwrap();
+    /// assert_eq!(board.get_tile(0, 0, 0).unwrap(), MaybeTile::Decided(Color::Green));
+    /// assert_eq!(board.get_tile(0, 1, 0).unwrap(), MaybeTile::Decided(Color::Blue));
+    /// ```
     pub fn set_tile(&mut self, tile: MaybeTile<T>, row: usize, col: usize, layer: usize) -> Result<(), BadPlacementError> {
         match tile {
-            MaybeTile::Undecided(options) => match &self.tiles[layer][row][col] {
-                MaybeTile::Undecided(possibilities) => if possibilities.is_superset(&options) {
-                    self.tiles[layer][row][col] = MaybeTile::Undecided(options.clone());
-                    let changes = self.propagate_possibilities(&options, row, col, layer);
-                    let mut v = vec![];
-                    for ((row, col, layer), new_possibilities) in changes {
-                        match &mut self.tiles[layer][row][col] {
-                            MaybeTile::Undecided(_) => {
-                                self.tiles[layer][row][col] = MaybeTile::Undecided(new_possibilities);
-                                v.push((row, col, layer))
-                            },
-                            MaybeTile::Decided(t) => {
-                                if !new_possibilities.contains(t) {
-                                    unreachable!()
-                                }
-                            },
+            MaybeTile::Undecided(options) => {
+                let options = match self.rules.to_tile_set(&options) {
+                    Some(options) => options,
+                    None => return Err(BadPlacementError::NotAllPossible),
+                };
+                match &self.tiles[layer][row][col] {
+                    Slot::Undecided(possibilities) => if possibilities.is_superset(&options) {
+                        match self.place_by_hand((row, col, layer), Slot::Undecided(options)) {
+                            Ok(()) => Ok(()),
+                            Err(_) => Err(BadPlacementError::NotAllPossible),
                         }
-                    }
-                    let new_branch = DecisionBranch::new(row, col, layer);
-                    self.decision_stack.push(new_branch);
-                    let current_branch = self.decision_stack.last_mut().unwrap();
-                    current_branch.temp_decided_coords.extend(v.into_iter());
-                    Ok(())
-                } else {
-                    Err(BadPlacementError::NotAllPossible)
-                },
-                MaybeTile::Decided(tile) => if options.contains(tile) {
-                    Err(BadPlacementError::TileAlreadyPlaced)
-                } else {
-                    Err(BadPlacementError::TileOccupied)
-                },
+                    } else {
+                        Err(BadPlacementError::NotAllPossible)
+                    },
+                    Slot::Decided(tile) => if options.contains(*tile) {
+                        Err(BadPlacementError::TileAlreadyPlaced)
+                    } else {
+                        Err(BadPlacementError::TileOccupied)
+                    },
+                }
             },
-            MaybeTile::Decided(tile) => match &self.tiles[layer][row][col] {
-                MaybeTile::Undecided(possibilities) => if possibilities.contains(&tile) {
-                    let new_branch = DecisionBranch::new(row, col, layer);
-                    self.decision_stack.push(new_branch);
-                    self.change_tile(tile, row, col, layer);
-                    Ok(())
-                } else {
-                    Err(BadPlacementError::ImpossibleTile)
-                },
-                MaybeTile::Decided(old_tile) => if tile == *old_tile {
-                    Err(BadPlacementError::TileAlreadyPlaced)
-                } else {
-                    Err(BadPlacementError::TileOccupied)
-                },
+            MaybeTile::Decided(tile) => {
+                let tile = match self.rules.index(&tile) {
+                    Some(tile) => tile,
+                    None => return Err(BadPlacementError::ImpossibleTile),
+                };
+                match &self.tiles[layer][row][col] {
+                    Slot::Undecided(possibilities) => if possibilities.contains(tile) {
+                        match self.place_by_hand((row, col, layer), Slot::Decided(tile)) {
+                            Ok(()) => Ok(()),
+                            Err(_) => Err(BadPlacementError::ImpossibleTile),
+                        }
+                    } else {
+                        Err(BadPlacementError::ImpossibleTile)
+                    },
+                    Slot::Decided(old_tile) => if tile == *old_tile {
+                        Err(BadPlacementError::TileAlreadyPlaced)
+                    } else {
+                        Err(BadPlacementError::TileOccupied)
+                    },
+                }
             },
         }
     }
 
+    /// Places `slot` for [Board::set_tile()], leaving the board as it was if it breaks any rule
+    fn place_by_hand(&mut self, coord: (usize, usize, usize), slot: Slot) -> Result<(), Contradiction> {
+        // The changes get a branch of their own until they are known to be right
+        let (row, col, layer) = coord;
+        self.decision_stack.push(DecisionBranch::new(row, col, layer));
+        let placed = self.place(coord, &slot);
+        let mut branch = self.decision_stack.pop().unwrap();
+        match placed {
+            Ok(()) => {
+                if let Some(current_branch) = self.decision_stack.last_mut() {
+                    current_branch.trail.append(&mut branch.trail)
+                }
+                self.user_placements.push((coord, slot));
+            },
+            Err(_) => self.undo(&mut branch),
+        }
+        placed
+    }
+
     /// Returns the tile at a specified position
     pub fn get_tile(&self, row: usize, col: usize, layer: usize) -> Result<MaybeTile<T>, OutOfBoardError> {
+        Ok(self.to_maybe_tile(self.get_slot(row, col, layer)?))
+    }
+
+    /// Returns the tiles that can still be placed at a specified position.
+    /// If the position has already been decided, only that tile is returned.
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// let mut board = procedural::Board::<MyTile>::new(5, 5, 1);
+    /// assert_eq!(board.get_possibilities(0, 0, 0).unwrap().count(), 3);
+    /// board.set_tile(procedural::MaybeTile::Decided(MyTile::Red), 0, 0, 0).unwrap();
+    /// assert_eq!(board.get_possibilities(0, 0, 0).unwrap().collect::<Vec<_>>(), vec![MyTile::Red]);
+    /// ```
+    pub fn get_possibilities(&self, row: usize, col: usize, layer: usize) -> Result<impl Iterator<Item = T> + '_, OutOfBoardError> {
+        Ok(self.get_slot(row, col, layer)?.iter().map(|tile| self.rules.tile(tile)))
+    }
+
+    fn get_slot(&self, row: usize, col: usize, layer: usize) -> Result<&Slot, OutOfBoardError> {
         match self.tiles.get(layer) {
             Some(r) => match r.get(row) {
                 Some(c) => match c.get(col) {
-                    Some(t) => Ok((*t).clone()),
+                    Some(t) => Ok(t),
                     None => Err(OutOfBoardError),
                 },
                 None => Err(OutOfBoardError),
@@ -478,217 +1616,449 @@ where
         }
     }
 
-    /// Generate n tiles at once
-    pub fn generate_n(&mut self, n: u32) -> Result<bool, ImpossibleBoardError> {
+    fn to_maybe_tile(&self, slot: &Slot) -> MaybeTile<T> {
+        match slot {
+            Slot::Undecided(possibilities) => MaybeTile::Undecided(self.rules.to_hash_set(possibilities)),
+            Slot::Decided(tile) => MaybeTile::Decided(self.rules.tile(*tile)),
+        }
+    }
+
+    /// Generate n tiles at once. Stops early if an [Observer] cancels the generation
+    pub fn generate_n(&mut self, n: u32) -> Result<bool, ImpossibleBoardError<T>> {
+        self.cancelled = false;
         let mut complete = false;
         for _ in 0..n {
             if self.generate_1()? {
                 complete = true;
                 break
             }
+            if self.cancelled {
+                break
+            }
         };
         Ok(complete)
     }
 
-    /// Returns the [BranchStatus] of the current branch of the decision tree
-    pub fn get_status(&self) -> BranchStatus {
-        let undecideds_left = self.tiles.get(self.current_layer).unwrap().iter().any(|v| v.iter().any(|t| match t {
-            MaybeTile::Undecided(_) => true,
-            MaybeTile::Decided(_) => false,
-        }));
-
-        let impossibilities = self.tiles.get(self.current_layer).unwrap().iter().any(|v| v.iter().any(|t| match t {
-            MaybeTile::Undecided(possibilities) => possibilities.len() == 0,
-            MaybeTile::Decided(_) => false,
-        }));
-
-        let branch_end = self.tiles.get(self.current_layer).unwrap().iter().enumerate().all(|(i, v)| v.iter().enumerate().all(|(j, t)| match t {
-            MaybeTile::Undecided(possibilities) => {
-                if possibilities.len() > 0 {
-                    if let Some(current_branch) = self.decision_stack.last() {
-                        if current_branch.deciding_coord == (i, j, self.current_layer) {
-                            if possibilities.len() <= current_branch.tried_tiles.len() {
-                                true
-                            } else {
-                                false
-                            }
-                        } else if current_branch.dead_ends.contains(&(i, j, self.current_layer)) {
-                            true
-                        } else {
-                            false
-                        }
-                    } else {
-                        if self.dead_ends.contains(&(i, j, self.current_layer)) {
-                            true
-                        } else {
-                            false
-                        }
-                    }
-                } else {
-                    true
-                }
-            },
-            MaybeTile::Decided(_) => true,
-        }));
+    /// Generate until the board is full or the [Budget] runs out, so a big board can be generated a little at a time.
+    /// The backtracks of the budget are counted from the start of this call.
+    /// ```
+    #[doc = include_str!("../doc_helpers/MyTile.rs")]
+    /// use std::time::Duration;
+    /// use procedural::{Budget, GenerationOutcome};
+    /// let mut board = procedural::Board::<MyTile>::new(20, 20, 1);
+    /// loop {
+    ///     match board.generate_with_budget(Budget::time(Duration::from_millis(1))) {
+    ///         GenerationOutcome::Complete => break,
+    ///         GenerationOutcome::BudgetExhausted | GenerationOutcome::Cancelled => (), // Do something else and continue later
+    ///         GenerationOutcome::Impossible(error) => panic!("{error}"),
+    ///     }
+    /// }
+    /// ```
+    pub fn generate_with_budget(&mut self, budget: Budget) -> GenerationOutcome<T> {
+        self.cancelled = false;
+        let backtracks = self.backtracks;
+        loop {
+            match self.generate_1() {
+                Ok(true) => return GenerationOutcome::Complete,
+                Ok(false) => (),
+                Err(error) => return GenerationOutcome::Impossible(error),
+            }
+            if self.cancelled {
+                return GenerationOutcome::Cancelled
+            }
+            if budget.is_exhausted(self.backtracks - backtracks) {
+                return GenerationOutcome::BudgetExhausted
+            }
+        }
+    }
+
+    /// How many times generating has had to go back to a previous decision since the board was created or cleaned
+    pub fn backtracks(&self) -> u64 {
+        self.backtracks
+    }
 
-        match (impossibilities, branch_end, undecideds_left) {
-            (_, _, false) => match self.tiles.get(self.current_layer+1) {
+    /// Returns the [BranchStatus] of the current branch of the decision tree
+    pub fn get_status(&self) -> BranchStatus {
+        if self.contradiction.is_some() {
+            return BranchStatus::DeadEnd
+        }
+
+        let undecideds_left = match &self.candidates {
+            Some(candidates) => candidates.undecided(self.current_layer) > 0,
+            None => self.tiles[self.current_layer].iter().flatten().any(|slot| matches!(slot, Slot::Undecided(_))),
+        };
+
+        match undecideds_left {
+            false => match self.tiles.get(self.current_layer+1) {
                 Some(_) => BranchStatus::CompleteLayer,
                 None => BranchStatus::Complete,
             },
-            (true, _, _) => BranchStatus::DeadEnd,
-            (_, true, _) => BranchStatus::DeadEnd,
-            (_, _, _) => BranchStatus::Incomplete,
+            true => BranchStatus::Incomplete,
         }
     }
 
-    fn get_undecided(&mut self) -> (usize, usize, usize) {
-        let options: Vec<_>;
-        loop {
-            let layer = self.tiles.get(self.current_layer).unwrap();
-            let opt = layer.iter()
-                .enumerate()
-                .flat_map(|(i, row)| {
-                    let board = &self;
-                    row.iter()
-                        .enumerate()
-                        .filter_map(move |(j, tile)| match tile {
-                            MaybeTile::Undecided(a) => if a.len() == 0 {
-                                None
-                            } else if let Some(current_branch) = board.decision_stack.last() {
-                                if current_branch.dead_ends.contains(&(i, j, board.current_layer)) {
-                                    None
-                                } else {
-                                    Some(((i, j, board.current_layer), a.len()))
-                                }
-                            } else {
-                                if board.dead_ends.contains(&(i, j, board.current_layer)) {
-                                    None
-                                } else {
-                                    Some(((i, j, board.current_layer), a.len()))    
-                                }
-                            },
-                            MaybeTile::Decided(_) => None,
-                        })
-                }).collect::<Vec<_>>();
-                let min = match opt.iter().min_by_key(|(_, key)| key) {
-                    Some((_, min)) => Some(*min),
-                    None => None,
-                };
-                match min {
-                    Some(min) => {
-                        options = opt.into_iter().filter(|(_, a)| *a == min).collect();
-                        break;
-                    },
-                    None => self.current_layer += 1, // This layer is done
-                }
+    /// Chooses the next slot to decide with the [CellSelector].
+    /// If a slot of the layer can't have any tile, returns the contradiction there instead
+    fn get_undecided(&mut self) -> Result<(usize, usize, usize), Contradiction> {
+        if self.candidates.is_none() {
+            self.candidates = Some(self.find_candidates());
+        }
+        // There is at least one, because there is at least one undecided slot
+        let (index, layer, empty) = self.candidates.as_mut().and_then(|candidates| candidates.first()).unwrap();
+        // The layers before it are done
+        self.current_layer = self.current_layer.max(layer);
+        let coord = self.coord(index);
+        if empty {
+            return Err(self.contradiction_at(coord, self.culprits[coord.2][coord.0][coord.1].clone()))
+        }
+        Ok(coord)
+    }
+
+    /// Scores every undecided slot of the board
+    fn find_candidates(&self) -> Candidates {
+        let mut candidates = Candidates::new(self.coords().count(), self.height as usize, self.tiebreak_seed);
+        for (index, coord) in self.coords().enumerate() {
+            let (row, col, layer) = coord;
+            if let Slot::Undecided(possibilities) = &self.tiles[layer][row][col] {
+                let score = self.cell_selector.score(self, row, col, layer);
+                candidates.update(index, layer, false, Some((score, possibilities.is_empty())));
+            }
+        }
+        candidates
+    }
+
+    /// Keeps [Board::candidates] up to date after the slot at `coord` changed from `previous`
+    fn update_candidate(&mut self, coord: (usize, usize, usize), previous: &Slot) {
+        if self.candidates.is_none() {
+            return
+        }
+        let (row, col, layer) = coord;
+        let undecided = match &self.tiles[layer][row][col] {
+            Slot::Undecided(possibilities) => Some((self.cell_selector.score(self, row, col, layer), possibilities.is_empty())),
+            Slot::Decided(_) => None,
+        };
+        let index = self.index(coord);
+        if let Some(candidates) = &mut self.candidates {
+            candidates.update(index, layer, matches!(previous, Slot::Undecided(_)), undecided);
         }
-        let mut rng = rand::thread_rng();
-        let option = options.iter().choose(&mut rng).unwrap().0;
-        option
     }
 
-    fn make_decision(&mut self, row: usize, col: usize, layer: usize) -> T {
-        let mut rng = rand::thread_rng();
+    /// Chooses one of the tiles that haven't been tried yet in the current branch, if there is any
+    fn make_decision(&mut self, row: usize, col: usize, layer: usize) -> Option<usize> {
+        let tried_tiles = match self.decision_stack.last() {
+            Some(current_branch) if current_branch.deciding_coord == (row, col, layer) => current_branch.tried_tiles.clone(),
+            _ => TileSet::new(),
+        };
         let mut weights = Vec::new();
         let options = match &self.tiles[layer][row][col] {
-            MaybeTile::Undecided(possibilities) => possibilities.iter()
+            Slot::Undecided(possibilities) => possibilities.iter()
+                .filter(|tile| !tried_tiles.contains(*tile))
                 .map(|tile| {
-                    weights.push(tile.get_distribution(layer));
+                    weights.push(self.tile_selector.weight(self, self.rules.tile(tile), row, col, layer));
                     tile
                 }).collect::<Vec<_>>(),
-            MaybeTile::Decided(_) => unreachable!(),
+            Slot::Decided(_) => unreachable!(),
         };
-        let dist = WeightedIndex::new(&weights).unwrap();
-        *options[dist.sample(&mut rng)]
-    }
-
-    fn propagate(&mut self, tile: T, row: usize, col: usize, layer: usize) -> Vec<(usize, usize, usize)> {
-        let mut v = vec![];
-        let mut prop_dir = |direction: T::Direction, v: &mut Vec<(usize, usize, usize)>| {
-            match direction.neighbour(row, col, layer, self.width, self.length, self.height) {
-                Ok((row, col, layer)) => {
-                    match &mut self.tiles[layer][row][col] {
-                        MaybeTile::Undecided(possibilities) => {
-                            v.push((row, col, layer));
-                            let len = possibilities.len();
-                            tile.propagate(possibilities, direction);
-                            if possibilities.len() == 1 {
-                                let remaining_tile = *possibilities.iter().next().unwrap();
-                                self.tiles[layer][row][col] = MaybeTile::Decided(remaining_tile);
-                                v.extend(self.propagate(remaining_tile, row, col, layer).into_iter());
-                            } else if possibilities.len() < len {
-                                let possibilities = possibilities.clone();
-                                let changes = self.propagate_possibilities(&possibilities, row, col, layer);
-                                for ((row, col, layer), new_possibilities) in changes {
-                                    match &mut self.tiles[layer][row][col] {
-                                        MaybeTile::Undecided(_) => {
-                                            self.tiles[layer][row][col] = MaybeTile::Undecided(new_possibilities);
-                                            v.push((row, col, layer))
-                                        },
-                                        MaybeTile::Decided(t) => {
-                                            if !new_possibilities.contains(t) {
-                                                unreachable!()
-                                            }
-                                        },
-                                    }
-                                }
-                            }
-                        },
-                        MaybeTile::Decided(_) => (),
-                    }
-                },
-                Err(_) => ()
+        match WeightedIndex::new(&weights) {
+            Ok(dist) => Some(options[dist.sample(&mut self.rng)]),
+            Err(_) => options.iter().choose(&mut self.rng).copied(),
+        }
+    }
+
+    /// Removes the possibilities that are not allowed by the neighbouring slots, starting from the slots in `queue`,
+    /// until no more possibilities can be removed.
+    /// Stops at the first slot that is left without possibilities, or at a broken count or connectivity constraint.
+    fn propagate(&mut self, mut queue: VecDeque<(usize, usize, usize)>) -> Result<(), Contradiction> {
+        let mut queued = queue.iter().copied().collect::<HashSet<_>>();
+        while let Some((row, col, layer)) = queue.pop_front() {
+            queued.remove(&(row, col, layer));
+            self.enforce_counts((row, col, layer), &mut queue, &mut queued)?;
+            let possibilities = self.tiles[layer][row][col].iter().collect::<TileSet>();
+            let culprits = self.culprits[layer][row][col].clone();
+            if possibilities.is_empty() {
+                return Err(self.contradiction_at((row, col, layer), culprits))
+            }
+            for i in 0..self.degree((row, col, layer)) {
+                let (d, neighbour) = match self.neighbour((row, col, layer), i) {
+                    Some(neighbour) => neighbour,
+                    None => continue,
+                };
+                let (row, col, layer) = neighbour;
+                let mut new_possibilities = self.rules.support(&possibilities, d);
+                let new_possibilities = match &self.tiles[layer][row][col] {
+                    Slot::Undecided(next_possibilities) => {
+                        new_possibilities.intersect_with(next_possibilities);
+                        if new_possibilities.len() == next_possibilities.len() {
+                            continue
+                        }
+                        new_possibilities
+                    },
+                    Slot::Decided(tile) => if new_possibilities.contains(*tile) {
+                        continue
+                    } else {
+                        let mut culprits = culprits;
+                        culprits.union_with(&self.culprits[layer][row][col]);
+                        return Err(self.contradiction_at(neighbour, culprits))
+                    },
+                };
+                self.reduce(neighbour, new_possibilities, &culprits)?;
+                if queued.insert(neighbour) {
+                    queue.push_back(neighbour)
+                }
             }
-        };
-        for direction in Direction::all() {
-            prop_dir(direction, &mut v);
         }
-        v
+        self.check_connectivity()
     }
 
-    fn propagate_possibilities(&self, possibilities: &HashSet<T>, row: usize, col: usize, layer: usize) -> Vec<((usize, usize, usize), HashSet<T>)> {
-        let mut v = vec![];
-        let prop_dir = |tile: T, direction: T::Direction| {
-            match direction.neighbour(row, col, layer, self.width, self.length, self.height) {
-                Ok((row, col, layer)) => {
-                    match &self.tiles[layer][row][col] {
-                        MaybeTile::Undecided(possibilities) => {
-                            let mut new_possibilities = possibilities.clone();
-                            tile.propagate(&mut new_possibilities, direction);
+    /// Leaves only `possibilities` in the undecided slot at `coord`, because of the decisions in `culprits`
+    fn reduce(&mut self, (row, col, layer): (usize, usize, usize), possibilities: TileSet, culprits: &DecisionSet) -> Result<(), Contradiction> {
+        let empty = possibilities.is_empty();
+        let (new_slot, event) = match possibilities.len() {
+            1 => {
+                let tile = possibilities.iter().next().unwrap();
+                (Slot::Decided(tile), Event::Collapsed { row, col, layer, tile: self.rules.tile(tile) })
+            },
+            len => (Slot::Undecided(possibilities), Event::Reduced { row, col, layer, possibilities: len }),
+        };
+        self.set_slot((row, col, layer), new_slot, culprits);
+        if empty {
+            return Err(self.contradiction_at((row, col, layer), self.culprits[layer][row][col].clone()))
+        }
+        self.notify(event);
+        Ok(())
+    }
 
-                            Some(new_possibilities)
-                        },
-                        MaybeTile::Decided(_) => None,
-                    }
-                },
-                Err(_) => None
+    /// Removes the tiles that reached their maximum from the rest of the slots, and places the tiles that need
+    /// every slot they can still be in to reach their minimum, queueing the slots that changed.
+    /// Fails if a tile is over its maximum or can't reach its minimum anymore. See [Board::set_count()]
+    ///
+    /// Does nothing if no limited tile changed since the last time. `coord` is the slot being propagated,
+    /// where the contradiction is reported
+    fn enforce_counts(
+        &mut self,
+        coord: (usize, usize, usize),
+        queue: &mut VecDeque<(usize, usize, usize)>,
+        queued: &mut HashSet<(usize, usize, usize)>
+    ) -> Result<(), Contradiction> {
+        if !std::mem::take(&mut self.counts_changed) {
+            return Ok(())
+        }
+        for i in 0..self.counts.len() {
+            let tile_count = &self.counts[i];
+            let (tile, count, decided, possible) = (tile_count.tile, tile_count.count, tile_count.decided, tile_count.possible());
+            // Not about the slot at `coord` or its neighbours, it's only the slot whose change was being propagated
+            let exceeded = |culprits| Contradiction { coord, constraints: vec![], culprits, count: Some((tile, count)) };
+            if decided > count.max {
+                return Err(exceeded(tile_count.decided_culprits.to_set()))
+            }
+            if possible < count.min {
+                // Only the decisions that took the tile from some slot can give it back
+                return Err(exceeded(tile_count.removed_culprits.to_set()))
+            }
+            let (culprits, needed) = if decided == count.max && possible > decided {
+                // No other slot can have it
+                (tile_count.decided_culprits.to_set(), false)
+            } else if possible == count.min && possible > decided {
+                // Every slot that can still have it needs it
+                (tile_count.removed_culprits.to_set(), true)
+            } else {
+                continue
+            };
+            for index in tile_count.undecided.iter().copied().collect::<Vec<_>>() {
+                let (row, col, layer) = self.coord(index);
+                let possibilities = match needed {
+                    true => TileSet::from_iter([tile]),
+                    false => {
+                        let mut possibilities = self.tiles[layer][row][col].iter().collect::<TileSet>();
+                        possibilities.remove(tile);
+                        possibilities
+                    },
+                };
+                self.reduce((row, col, layer), possibilities, &culprits).map_err(|contradiction| Contradiction {
+                    count: Some((tile, count)),
+                    ..contradiction
+                })?;
+                if queued.insert((row, col, layer)) {
+                    queue.push_back((row, col, layer))
+                }
             }
+        }
+        Ok(())
+    }
+
+    /// Fails if a set of [Board::add_connected_set()] has been split, or the ends of a [Board::add_path()] can't be joined anymore.
+    ///
+    /// Only the groups that may have been split since the last check are searched: the ones next to the slots that can't have
+    /// any of the tiles anymore and the ones with new members, which must reach one of the other members.
+    fn check_connectivity(&mut self) -> Result<(), Contradiction> {
+        for i in 0..self.connections.len() {
+            let cut = std::mem::take(&mut self.connections[i].cut);
+            let mut joined = std::mem::take(&mut self.connections[i].joined);
+            let mut connection = std::mem::take(&mut self.connections[i]);
+            let starts = if self.connections_unchecked {
+                joined = vec![];
+                (0..connection.members.len()).filter(|index| connection.members[*index]).collect()
+            } else if cut.is_empty() && joined.is_empty() {
+                self.connections[i] = connection;
+                continue
+            } else {
+                joined.retain(|index| connection.members[*index]);
+                joined.sort_unstable();
+                joined.dedup();
+                cut.iter().flat_map(|index| self.neighbour_indices(*index)).chain(joined.iter().copied()).collect()
+            };
+            let checked = self.check_connection(&mut connection, starts, &joined);
+            self.connections[i] = connection;
+            checked?;
+        }
+        self.connections_unchecked = false;
+        Ok(())
+    }
+
+    /// Fails if the members of `connection` are not all in the same group of slots that can have any of its tiles.
+    ///
+    /// A search starts from each of `starts`, and they go one slot at a time, joining when they meet, until all but one
+    /// have found their whole group. That one also has to find a member that is not `joined`, if it has any member.
+    /// All the members that are not `joined` must have been in the same group, and every group that may have been split
+    /// from it or that has `joined` members must have one of `starts`.
+    fn check_connection(&self, connection: &mut Connection, starts: Vec<usize>, joined: &[usize]) -> Result<(), Contradiction> {
+        let slot = |index: usize| {
+            let (row, col, layer) = self.coord(index);
+            &self.tiles[layer][row][col]
+        };
+        let culprits = |index: usize| {
+            let (row, col, layer) = self.coord(index);
+            &self.culprits[layer][row][col]
         };
-        for direction in T::Direction::all() {
-            match direction.neighbour(row, col, layer, self.width, self.length, self.height) {
-                Ok((row, col, layer)) => match &self.tiles[layer][row][col] {
-                    MaybeTile::Undecided(next_possibilities) => {
-                        let len = next_possibilities.len();
-                        let mut next_possibilities = HashSet::new();
-                        for tile in possibilities.iter() {
-                            match prop_dir(*tile, direction) {
-                                Some(h) => for t in h {
-                                    next_possibilities.insert(t);
-                                },
-                                None => (),
-                            };
+        if let Some((from, to)) = connection.ends {
+            if let Some(end) = [from, to].into_iter().find(|end| !slot(*end).any_of(&connection.tiles)) {
+                return Err(self.contradiction_at(self.coord(end), culprits(end).clone()))
+            }
+        }
+        // The slots found by an earlier check are told apart by its number, so they don't have to be forgotten
+        connection.checks = connection.checks.wrapping_add(1);
+        if connection.checks == 0 || connection.found.len() != connection.members.len() {
+            connection.found = vec![(0, 0); connection.members.len()];
+            connection.checks = 1;
+        }
+        let check = connection.checks;
+        let ends = connection.ends;
+        let (tiles, members, found) = (&connection.tiles, &connection.members, &mut connection.found);
+        let search_of = |found: &[(u32, usize)], index: usize| (found[index].0 == check).then_some(found[index].1);
+        let total = connection.total;
+        let old = |index: usize| members[index] && joined.binary_search(&index).is_err();
+        let any_old = total > joined.len();
+        // The search that every search joined
+        let mut joined_to = vec![];
+        let mut groups = vec![];
+        for start in starts {
+            if search_of(found, start).is_some() || !slot(start).any_of(tiles) {
+                continue
+            }
+            found[start] = (check, groups.len());
+            joined_to.push(groups.len());
+            let member = members[start].then_some(start);
+            groups.push(Group {
+                frontier: VecDeque::from([start]),
+                members: member.is_some() as usize,
+                member,
+                anchored: old(start)
+            });
+        }
+        let mut searching = (0..groups.len()).collect::<VecDeque<_>>();
+        let mut left = groups.len();
+        while let Some(g) = searching.pop_front() {
+            if joined_to[g] != g {
+                continue
+            }
+            let group = &groups[g];
+            if left == 1 && (group.members == 0 || group.anchored || !any_old) {
+                break
+            }
+            let Some(index) = groups[g].frontier.pop_front() else {
+                // The whole group has been found
+                left -= 1;
+                let group = &groups[g];
+                if group.members == total {
+                    return Ok(())
+                }
+                if let Some(inside) = group.member {
+                    let outside = (0..members.len())
+                        .find(|index| members[*index] && search_of(found, *index).map(|search| root(&mut joined_to, search)) != Some(g))
+                        .unwrap();
+                    // The decisions that removed the tiles from the slots around the group
+                    let mut culprits_of_split = DecisionSet::new();
+                    for index in 0..found.len() {
+                        if search_of(found, index).map(|search| root(&mut joined_to, search)) != Some(g) {
+                            continue
                         }
-                        if len != next_possibilities.len() {
-                            v.push(((row, col, layer), next_possibilities))
+                        for neighbour in self.neighbour_indices(index) {
+                            if !slot(neighbour).any_of(tiles) {
+                                culprits_of_split.union_with(culprits(neighbour))
+                            }
+                        }
+                    }
+                    if ends.is_none() {
+                        culprits_of_split.union_with(culprits(inside));
+                        culprits_of_split.union_with(culprits(outside));
+                    }
+                    return Err(self.contradiction_at(self.coord(outside), culprits_of_split))
+                }
+                continue
+            };
+            for neighbour in self.neighbour_indices(index) {
+                if !slot(neighbour).any_of(tiles) {
+                    continue
+                }
+                match search_of(found, neighbour) {
+                    None => {
+                        found[neighbour] = (check, g);
+                        let group = &mut groups[g];
+                        group.frontier.push_back(neighbour);
+                        if members[neighbour] {
+                            group.members += 1;
+                            group.member = Some(neighbour);
+                            group.anchored |= old(neighbour);
                         }
                     },
-                    MaybeTile::Decided(_) => (),
-                },
-                Err(_) => (),
+                    Some(search) => {
+                        let other = root(&mut joined_to, search);
+                        if other != g {
+                            joined_to[other] = g;
+                            left -= 1;
+                            let mut other = std::mem::take(&mut groups[other]);
+                            let group = &mut groups[g];
+                            if other.frontier.len() > group.frontier.len() {
+                                std::mem::swap(&mut group.frontier, &mut other.frontier)
+                            }
+                            group.frontier.extend(other.frontier);
+                            group.members += other.members;
+                            group.member = group.member.or(other.member);
+                            group.anchored |= other.anchored;
+                        }
+                    },
+                }
             }
+            searching.push_back(g);
         }
-        v
+        Ok(())
+    }
+
+    /// The position of the slot at `coord` in [Board::coords()]
+    fn index(&self, (row, col, layer): (usize, usize, usize)) -> usize {
+        (layer * self.length as usize + row) * self.width as usize + col
+    }
+
+    /// The (row, col, layer) at `index` of [Board::coords()]
+    fn coord(&self, index: usize) -> (usize, usize, usize) {
+        let (width, length) = (self.width as usize, self.length as usize);
+        (index / width % length, index % width, index / (width * length))
+    }
+
+    /// Every (row, col, layer) of the board
+    fn coords(&self) -> impl Iterator<Item = (usize, usize, usize)> {
+        let (width, length, height) = (self.width as usize, self.length as usize, self.height as usize);
+        (0..height).flat_map(move |layer| (0..length).flat_map(move |row| (0..width).map(move |col| (row, col, layer))))
     }
 
     #[cfg(feature = "view3d")]
@@ -698,8 +2068,9 @@ where
             for (i, row) in layer.iter().enumerate() {
                 for (j, tile) in row.iter().enumerate() {
                     match tile {
-                        MaybeTile::Undecided(_) => (),
-                        MaybeTile::Decided(tile) => {
+                        Slot::Undecided(_) => (),
+                        Slot::Decided(tile) => {
+                            let tile = self.rules.tile(*tile);
                             if tile.has_model() {
                                 te_state.place_custom_model(&tile.get_name(), gpu, (j as f32,k as f32,i as f32), None);
                             }
@@ -713,7 +2084,7 @@ where
     #[cfg(feature = "view3d")]
     /// Loads all models so they can be rendered 
     pub fn load_models(&self, gpu: &GpuState, te_state: &mut TeState) {
-        for tile in T::all() {
+        for tile in (0..self.rules.len()).map(|tile| self.rules.tile(tile)) {
             match tile.get_model() {
                 Some((vertices, indices, texture_name)) => {
                     let name = tile.get_name();
@@ -725,27 +2096,126 @@ where
         }
     }
 
-    fn go_back(&mut self) -> Result<(), ImpossibleBoardError> {
-        if let Some(current_branch) = self.decision_stack.pop() {
-            let (row, col, layer) = current_branch.deciding_coord;
-            self.tiles[layer][row][col] = MaybeTile::Undecided(T::possibles(layer));
-            for (row, col, layer) in current_branch.temp_decided_coords.iter() {
-                self.tiles[*layer][*row][*col] = MaybeTile::Undecided(T::possibles(*layer));
-            };
-            self.recalculate(row, col, layer);
-            for (row, col, layer) in current_branch.temp_decided_coords.iter() {
-                self.recalculate(*row, *col, *layer);
+    /// How many neighbours the slot at `coord` can have
+    fn degree(&self, (_, col, _): (usize, usize, usize)) -> usize {
+        match &self.edges {
+            Some(edges) => edges[col].len(),
+            None => self.rules.directions().len(),
+        }
+    }
+
+    /// The `i`th neighbour of the slot at `coord`, if it's in the board, and the index of the direction it is in
+    fn neighbour(&self, (row, col, layer): (usize, usize, usize), i: usize) -> Option<(usize, (usize, usize, usize))> {
+        match &self.edges {
+            Some(edges) => {
+                let (d, node) = edges[col][i];
+                Some((d, (0, node, 0)))
+            },
+            None => {
+                let neighbour = self.rules.directions()[i].neighbour(row, col, layer, self.width, self.length, self.height).ok()?;
+                Some((i, neighbour))
+            },
+        }
+    }
+
+    /// The position in [Board::coords()] of every neighbour of the slot at position `index`
+    fn neighbour_indices(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let coord = self.coord(index);
+        (0..self.degree(coord)).filter_map(move |i| self.neighbour(coord, i)).map(|(_, neighbour)| self.index(neighbour))
+    }
+
+    /// Finds out which neighbours left the slot at `coord` without possibilities
+    fn contradiction_at(&self, coord: (usize, usize, usize), culprits: DecisionSet) -> Contradiction {
+        let (_, _, layer) = coord;
+        let possibles = self.rules.possibles(layer);
+        let constraints = (0..self.degree(coord)).filter_map(|i| {
+            let (d, (row, col, layer)) = self.neighbour(coord, i)?;
+            let tiles = self.tiles[layer][row][col].iter().collect::<TileSet>();
+            let restricts = possibles.iter().any(|tile| !self.rules.allows_any(tile, d, &tiles));
+            restricts.then_some((d, (row, col, layer), tiles))
+        }).collect();
+        Contradiction { coord, constraints, culprits, count: None }
+    }
+
+    fn impossible(&self) -> ImpossibleBoardError<T> {
+        let (coord, constraints) = match &self.last_contradiction {
+            Some(contradiction) => (Some(contradiction.coord), contradiction.constraints.iter().map(|(d, coord, tiles)| NeighbourConstraint {
+                direction: self.rules.directions()[*d],
+                coord: *coord,
+                tiles: tiles.iter().map(|tile| self.rules.tile(tile)).collect()
+            }).collect()),
+            None => (None, vec![]),
+        };
+        let count = self.last_contradiction.as_ref()
+            .and_then(|contradiction| contradiction.count)
+            .map(|(tile, count)| (self.rules.tile(tile), count));
+        ImpossibleBoardError {
+            coord,
+            constraints,
+            count,
+            backtracks: self.backtracks
+        }
+    }
+
+    /// Goes back to the latest decision that led to the contradiction, undoing it and every decision made after it.
+    /// If its slot has tiles that haven't been tried yet, generating will continue with them.
+    /// Otherwise, it goes back to the latest decision that made any of its tiles fail.
+    ///
+    /// Tiles placed by hand are not decisions, so they never stop it. The ones it undoes are placed again.
+    fn go_back(&mut self) -> Result<(), ImpossibleBoardError<T>> {
+        let mut conflict = match &self.contradiction {
+            Some(contradiction) => contradiction.culprits.clone(),
+            None => DecisionSet::new(),
+        };
+        while let Some(culprit) = conflict.last() {
+            let mut current_branch = match self.decision_stack.pop() {
+                Some(current_branch) => current_branch,
+                None => break,
             };
-            if let Some(previous_branch) = self.decision_stack.last_mut() {
-                previous_branch.dead_ends.push(current_branch.deciding_coord);
-                self.current_layer = previous_branch.deciding_coord.2;
-            } else{
-                self.current_layer = 0;
-                self.dead_ends.push(current_branch.deciding_coord)
-            }
-            Ok(())
-        } else {
-            Err(ImpossibleBoardError)
+            self.backtracks += 1;
+            self.undo(&mut current_branch);
+            let (row, col, layer) = current_branch.deciding_coord;
+            self.notify(Event::Backtracked { row, col, layer });
+            if self.decision_stack.len() > culprit {
+                // This decision had nothing to do with the contradiction
+                continue
+            }
+            conflict.remove(culprit);
+            current_branch.conflicts.union_with(&conflict);
+            let untried = self.tiles[layer][row][col].iter().any(|tile| !current_branch.tried_tiles.contains(tile));
+            if untried {
+                self.current_layer = layer;
+                self.contradiction = None;
+                self.decision_stack.push(current_branch);
+                if let Err(contradiction) = self.place_again() {
+                    self.set_contradiction(contradiction)
+                }
+                return Ok(())
+            }
+            conflict = current_branch.conflicts;
+        }
+        Err(self.impossible())
+    }
+
+    fn undo(&mut self, branch: &mut DecisionBranch) {
+        while let Some(((row, col, layer), slot, culprits)) = branch.trail.pop() {
+            let previous = std::mem::replace(&mut self.tiles[layer][row][col], slot);
+            let index = self.index((row, col, layer));
+            for tile_count in &mut self.counts {
+                self.counts_changed |= tile_count.change(index, &previous, &self.tiles[layer][row][col]);
+            }
+            for connection in &mut self.connections {
+                connection.change(index, &previous, &self.tiles[layer][row][col]);
+            }
+            self.update_candidate((row, col, layer), &previous);
+            self.culprits[layer][row][col] = culprits;
+        }
+        while let Some((i, decided, added)) = branch.count_trail.pop() {
+            let tile_count = &mut self.counts[i];
+            match decided {
+                true => tile_count.decided_culprits.remove(&added),
+                false => tile_count.removed_culprits.remove(&added),
+            }
         }
     }
 
@@ -753,39 +2223,146 @@ where
         if let Some(current_branch) = self.decision_stack.last() {
             let (row, col, layer) = current_branch.deciding_coord;
             match &self.tiles[layer][row][col] {
-                MaybeTile::Undecided(_) => true,
-                MaybeTile::Decided(_) => false,
+                Slot::Undecided(_) => true,
+                Slot::Decided(_) => false,
             }
         } else {
             false
         }
     }
+}
 
-    fn recalculate(&mut self, row: usize, col: usize, layer: usize) {
-        let mut recalc = |direction: T::Direction| {
-            match direction.neighbour(row, col, layer, self.width, self.length, self.height) {
-                Ok((row_t, col_t, layer_t)) => {
-                    match self.tiles[layer_t][row_t][col_t] {
-                        MaybeTile::Undecided(_) => (),
-                        MaybeTile::Decided(tile) => {
-                            let possibilities = match &mut self.tiles[layer][row][col] {
-                                MaybeTile::Undecided(possibilities) => possibilities,
-                                MaybeTile::Decided(_) => unreachable!(),
-                            };
-                            tile.propagate(possibilities, direction.opposite());
-                            if possibilities.len() == 1 {
-                                self.tiles[layer_t][row_t][col_t] = MaybeTile::Decided(*possibilities.iter().next().unwrap())
-                            }
-                        },
-                    }
-                },
-                Err(_) => ()
-            }
-        };
-        for direction in Direction::all() {
-            recalc(direction);
+/// The rules of a loaded board. The ones that came from a [RuleSet] have to be given again
+#[cfg(feature = "serde")]
+fn default_rules<T: BoardTile>() -> Rules<T> {
+    match T::rule_set() {
+        Some(rule_set) => rule_set.rules,
+        None => Rules::from_table(vec![], vec![], vec![]),
+    }
+}
+
+/// Loads [Board::tiles_fingerprint], failing if the tiles of [BoardTile::rule_set()] aren't the ones the board was saved with,
+/// for example because a variant was added to the enum of the tile. The ones of a [RuleSet] are checked by [Board::set_rule_set()]
+#[cfg(feature = "serde")]
+fn check_fingerprint<'de, T, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    T: BoardTile,
+    D: serde::Deserializer<'de>
+{
+    let fingerprint = u64::deserialize(deserializer)?;
+    match T::rule_set() {
+        Some(rule_set) if rule_set.rules.fingerprint() != fingerprint => {
+            Err(serde::de::Error::custom("the board was saved with different tiles"))
+        },
+        _ => Ok(fingerprint),
+    }
+}
+
+fn default_cell_selector<T: BoardTile>() -> Box<dyn CellSelector<T>> {
+    Box::new(selection::MinPossibilities)
+}
+
+fn default_tile_selector<T: BoardTile>() -> Box<dyn TileSelector<T>> {
+    Box::new(selection::Distribution)
+}
+
+/// Sorts coordinates, so the same seed always leads to the same choices
+fn ordered_coords(coords: HashSet<(usize, usize, usize)>) -> VecDeque<(usize, usize, usize)> {
+    let mut coords = coords.into_iter().collect::<Vec<_>>();
+    coords.sort();
+    coords.into()
+}
+
+/// Sorts tiles in an order that doesn't depend on the random state of a [HashSet],
+/// so the same seed always leads to the same choices.
+///
+/// Tiles are sorted by their [Fnv1a] hash, which (unlike [std::collections::hash_map::DefaultHasher])
+/// doesn't change between Rust versions or platforms, so saved seeds keep giving the same boards.
+fn ordered<'a, T>(tiles: impl Iterator<Item = &'a T>) -> Vec<T>
+where
+    T: BoardTile + 'a
+{
+    let mut tiles = tiles.map(|tile| {
+        let mut hasher = Fnv1a::new();
+        tile.hash(&mut hasher);
+        (hasher.finish(), tile.clone())
+    }).collect::<Vec<_>>();
+    tiles.sort_by_key(|(key, _)| *key);
+    tiles.into_iter().map(|(_, tile)| tile).collect()
+}
+
+/// The mixing function of splitmix64. Unlike [std::collections::hash_map::DefaultHasher], it never changes,
+/// so everything derived from a seed is always the same
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// The 64 bit FNV-1a hash. Integers are hashed as little endian bytes, so the hash is the same on every platform
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    fn new() -> Fnv1a {
+        Fnv1a(Fnv1a::OFFSET_BASIS)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(Fnv1a::PRIME)
         }
     }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        // The same on 32 and 64 bit platforms
+        self.write_u64(i as u64)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16)
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32)
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64)
+    }
 }
 
 #[cfg(feature = "view3d")]
@@ -818,9 +2395,12 @@ fn get_model(gpu: &GpuState, te_state: &mut TeState, name: String, vertices: Vec
     te_renderer::model::Model{ meshes, transparent_meshes: vec![], materials }
 }
 
+#[derive(Debug)]
 /// Returned when trying to access a position outside of the board
 pub struct CoordError;
 
+impl std::error::Error for CoordError {}
+
 macro_rules! direction {
     () => {
         /// All the possible directions
@@ -829,9 +2409,24 @@ macro_rules! direction {
         fn neighbour(&self, row: usize, col: usize, layer: usize, width: u32, length: u32, height: u32) -> Result<(usize, usize, usize), CoordError>;
         /// The direction that works exactly opposite when using [Direction::neighbour()]
         fn opposite(&self) -> Self;
+        /// How the slots are arranged, used when printing the board. A square grid by default
+        fn layout() -> Layout {
+            Layout::Square
+        }
     }
 }
 
+/// How the slots of a layer are arranged. See [Direction::layout()]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Every row is right below the previous one
+    Square,
+    /// Odd rows are shifted half a slot to the right, like [directions::PointyHex]
+    StaggeredRows,
+    /// Odd columns are shifted half a slot down, like [directions::FlatHex]
+    StaggeredColumns
+}
+
 #[cfg(feature = "validate")]
 /// Directions that are relevant to decide if a tile can be placed or not in a certain position
 pub trait Direction: Sized + Copy + Debug {
@@ -845,10 +2440,11 @@ pub trait Direction: Sized + Copy {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// Represents the various states that can have a slot in the board
 pub enum MaybeTile<T>
 where
-    T: Tile,
+    T: BoardTile,
 {
     /// The slot has more than 1 valid option.
     Undecided(HashSet<T>),
@@ -856,6 +2452,136 @@ where
     Decided(T),
 }
 
+/// Slots that must be joined by slots that can have any of `tiles`, by their index in [Rules].
+/// See [Board::add_connected_set()] and [Board::add_path()]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Connection {
+    tiles: TileSet,
+    /// The ends of the path by their index in the board, or None if the slots to join are all the ones that can only have tiles of the set
+    ends: Option<(usize, usize)>,
+    /// If every slot of the board is one of the slots to join
+    members: Vec<bool>,
+    /// How many slots there are to join
+    total: usize,
+    /// The slots that can't have any of the tiles anymore since the last check, by their index in the board
+    cut: Vec<usize>,
+    /// The slots that became members since the last check, by their index in the board
+    joined: Vec<usize>,
+    /// The number of the last check
+    #[cfg_attr(feature = "serde", serde(skip))]
+    checks: u32,
+    /// The number of the check that last found every slot, and the search of that check that found it
+    #[cfg_attr(feature = "serde", serde(skip))]
+    found: Vec<(u32, usize)>
+}
+
+impl Connection {
+    fn new(tiles: TileSet, ends: Option<(usize, usize)>) -> Connection {
+        Connection { tiles, ends, ..Connection::default() }
+    }
+
+    /// Updates the members after the slot at `index` changed from `previous` to `slot`, and remembers what has to be checked
+    fn change(&mut self, index: usize, previous: &Slot, slot: &Slot) {
+        if previous.any_of(&self.tiles) && !slot.any_of(&self.tiles) {
+            self.cut.push(index)
+        }
+        if self.ends.is_some() {
+            return
+        }
+        match (previous.only(&self.tiles), slot.only(&self.tiles)) {
+            (false, true) => {
+                self.members[index] = true;
+                self.total += 1;
+                self.joined.push(index)
+            },
+            (true, false) => {
+                self.members[index] = false;
+                self.total -= 1;
+            },
+            _ => (),
+        }
+    }
+}
+
+/// The slots found by one of the searches of [Board::check_connection()]
+#[derive(Default)]
+struct Group {
+    /// The slots found whose neighbours haven't been looked at yet
+    frontier: VecDeque<usize>,
+    /// How many members of the connection have been found, and one of them
+    members: usize,
+    member: Option<usize>,
+    /// If it found a member that was already one the last time the connection was checked
+    anchored: bool
+}
+
+/// The search that `search` ended up joining, making the ones in between point to it
+fn root(joined_to: &mut [usize], search: usize) -> usize {
+    let mut root = search;
+    while joined_to[root] != root {
+        root = joined_to[root]
+    }
+    let mut search = search;
+    while joined_to[search] != root {
+        let next = joined_to[search];
+        joined_to[search] = root;
+        search = next;
+    }
+    root
+}
+
+/// Like [MaybeTile], but with the index each tile has in [Rules] instead of the tile itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum Slot {
+    Undecided(TileSet),
+    Decided(usize),
+}
+
+impl Slot {
+    /// Amount of tiles that can still be in this slot
+    fn len(&self) -> usize {
+        match self {
+            Slot::Undecided(possibilities) => possibilities.len(),
+            Slot::Decided(_) => 1,
+        }
+    }
+
+    /// If `tile` can still be in this slot
+    fn contains(&self, tile: usize) -> bool {
+        match self {
+            Slot::Undecided(possibilities) => possibilities.contains(tile),
+            Slot::Decided(decided) => *decided == tile,
+        }
+    }
+
+    /// If any of `tiles` can still be in this slot
+    fn any_of(&self, tiles: &TileSet) -> bool {
+        match self {
+            Slot::Undecided(possibilities) => possibilities.intersects(tiles),
+            Slot::Decided(decided) => tiles.contains(*decided),
+        }
+    }
+
+    /// If only tiles of `tiles` can be in this slot
+    fn only(&self, tiles: &TileSet) -> bool {
+        match self {
+            Slot::Undecided(possibilities) => tiles.is_superset(possibilities),
+            Slot::Decided(decided) => tiles.contains(*decided),
+        }
+    }
+
+    /// The tiles that can still be in this slot
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let (possibilities, decided) = match self {
+            Slot::Undecided(possibilities) => (Some(possibilities), None),
+            Slot::Decided(tile) => (None, Some(*tile)),
+        };
+        possibilities.into_iter().flat_map(|possibilities| possibilities.iter()).chain(decided)
+    }
+}
+
 macro_rules! tile {
     () => {
         /// Directions that are relevant to this type of tile.
@@ -876,6 +2602,8 @@ macro_rules! tile {
         /// If this tile has a model or not (is invisible)
         fn has_model(&self) -> bool;
         /// How the rest of tiles will react when this one is decided
+        ///
+        /// [Board] doesn't call this method, it precomputes the result of [Tile::get_rules()] for every pair of tiles once instead.
         fn propagate(&self, possibilities: &mut HashSet<Self>, direction: Self::Direction) {
             let can_stay = self.get_rules();
             let mut to_remove = vec![];
@@ -895,6 +2623,71 @@ macro_rules! tile {
     }
 }
 
+macro_rules! board_tile {
+    () => {
+        /// Directions that are relevant to this type of tile.
+        type Direction: Direction;
+        #[cfg(feature = "view3d")]
+        /// **distinct** name, one for each tile with a different model.
+        fn get_name(&self) -> String;
+        #[cfg(feature = "view3d")]
+        /// If the model has a model, returns its vertex and triangle indices
+        fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)>;
+        #[cfg(feature = "view3d")]
+        /// If this tile has a model or not (is invisible)
+        fn has_model(&self) -> bool;
+        /// The rules followed by boards of this tile unless they are given other ones.
+        /// Every [Tile] has them, boards of other tiles can only be created with [Board::with_rule_set()]
+        fn rule_set() -> Option<RuleSet<Self>> {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "validate")]
+/// Anything that can be placed in a [Board].
+///
+/// Every [Tile] is a [BoardTile]. Tiles whose rules are only known at runtime, like the patterns of the overlapping model,
+/// implement only [BoardTile], so their boards can only be created with [Board::with_rule_set()].
+pub trait BoardTile: Sized + Eq + PartialEq + Hash + Clone + Debug {
+    board_tile!();
+}
+
+#[cfg(not(feature = "validate"))]
+/// Anything that can be placed in a [Board].
+///
+/// Every [Tile] is a [BoardTile]. Tiles whose rules are only known at runtime, like the patterns of the overlapping model,
+/// implement only [BoardTile], so their boards can only be created with [Board::with_rule_set()].
+pub trait BoardTile: Sized + Eq + PartialEq + Hash + Clone {
+    board_tile!();
+}
+
+impl<T> BoardTile for T
+where
+    T: Tile
+{
+    type Direction = <T as Tile>::Direction;
+
+    #[cfg(feature = "view3d")]
+    fn get_name(&self) -> String {
+        <T as Tile>::get_name(self)
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)> {
+        <T as Tile>::get_model(self)
+    }
+
+    #[cfg(feature = "view3d")]
+    fn has_model(&self) -> bool {
+        <T as Tile>::has_model(self)
+    }
+
+    fn rule_set() -> Option<RuleSet<Self>> {
+        Some(RuleSet { rules: Rules::new() })
+    }
+}
+
 #[cfg(feature = "validate")]
 /// Represents a tile of the board
 pub trait Tile: Sized + Eq + PartialEq + Hash + Clone + Copy + Debug {
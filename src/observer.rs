@@ -0,0 +1,107 @@
+//! Follow the progress of the generation while it happens. See [Observer]
+
+use crate::BoardTile;
+
+/// Gets notified of everything that happens while generating a [Board](crate::Board).
+/// See [Board::add_observer()](crate::Board::add_observer())
+///
+/// Any `FnMut(&Event<T>) -> Control` is also an [Observer]
+/// ```
+#[doc = include_str!("../doc_helpers/MyTile.rs")]
+/// use procedural::observer::{Control, Event};
+/// let mut board = procedural::Board::<MyTile>::new(5, 5, 1);
+/// // Stop after 3 decisions
+/// let mut decisions = 0;
+/// board.add_observer(move |event: &Event<MyTile>| match event {
+///     Event::BranchPushed { .. } => {
+///         decisions += 1;
+///         if decisions == 3 { Control::Cancel } else { Control::Continue }
+///     },
+///     _ => Control::Continue
+/// });
+/// board.generate().unwrap();
+/// assert!(board.is_cancelled());
+/// ```
+pub trait Observer<T>
+where
+    T: BoardTile
+{
+    /// Called after every [Event]. Return [Control::Cancel] to stop generating
+    fn notify(&mut self, event: &Event<T>) -> Control;
+}
+
+impl<T, F> Observer<T> for F
+where
+    T: BoardTile,
+    F: FnMut(&Event<T>) -> Control
+{
+    fn notify(&mut self, event: &Event<T>) -> Control {
+        self(event)
+    }
+}
+
+/// Something that happened while generating
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<T>
+where
+    T: BoardTile
+{
+    /// A slot has been decided, either by choosing its tile or because it was the only one left
+    Collapsed {
+        /// Row of the slot
+        row: usize,
+        /// Column of the slot
+        col: usize,
+        /// Layer of the slot
+        layer: usize,
+        /// The tile placed in the slot
+        tile: T
+    },
+    /// Some possibilities of a slot have been removed, but it's still undecided
+    Reduced {
+        /// Row of the slot
+        row: usize,
+        /// Column of the slot
+        col: usize,
+        /// Layer of the slot
+        layer: usize,
+        /// How many tiles can still be placed in the slot
+        possibilities: usize
+    },
+    /// A new decision has been made at this slot
+    BranchPushed {
+        /// Row of the slot
+        row: usize,
+        /// Column of the slot
+        col: usize,
+        /// Layer of the slot
+        layer: usize
+    },
+    /// The decision made at this slot has been undone, because it led to a dead end
+    Backtracked {
+        /// Row of the slot
+        row: usize,
+        /// Column of the slot
+        col: usize,
+        /// Layer of the slot
+        layer: usize
+    },
+    /// All the slots of this layer are decided, generation continues in the next one
+    LayerCompleted {
+        /// The layer that has been completed
+        layer: usize
+    },
+    /// Generating gave up and started over. See [RestartPolicy](crate::RestartPolicy)
+    Restarted,
+    /// The whole board is decided
+    Finished
+}
+
+/// What generating should do after notifying an [Observer]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Keep generating
+    Continue,
+    /// Stop generating. The board is left as is, and generating again will continue from there
+    Cancel
+}
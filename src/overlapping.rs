@@ -0,0 +1,221 @@
+//! Generate images that look like a sample image. See [OverlappingModel]
+
+use std::collections::HashMap;
+
+use image::{Rgba, RgbaImage};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+#[cfg(feature = "view3d")]
+use te_renderer::model::ModelVertex;
+
+use crate::{Board, BoardTile, Direction, ImpossibleBoardError, MaybeTile, RuleSet};
+use crate::directions::Direction4;
+use crate::rules::Rules;
+use crate::tile_set::TileSet;
+
+/// The overlapping model of wave function collapse: every NxN square of pixels of the sample is a pattern,
+/// and the output is made of patterns that overlap with their neighbours exactly like they can overlap in the sample.
+///
+/// Every slot of the board is the top-left corner of a pattern, so the board of an image of `width`x`height` pixels
+/// has `width - n + 1` columns and `height - n + 1` rows.
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use procedural::overlapping::{OverlappingModel, OverlappingOptions};
+/// let black = Rgba([0, 0, 0, 255]);
+/// let white = Rgba([255, 255, 255, 255]);
+/// // Vertical black and white stripes
+/// let sample = RgbaImage::from_fn(6, 6, |x, _| if x % 3 == 0 { black } else { white });
+/// let model = OverlappingModel::new(&sample, 3, OverlappingOptions::default());
+/// let output = model.generate(20, 10, 42).unwrap();
+/// assert_eq!(output.dimensions(), (20, 10));
+/// // Every column is all black or all white
+/// for x in 0..20 {
+///     assert!((0..10).all(|y| output.get_pixel(x, y) == output.get_pixel(x, 0)));
+/// }
+/// ```
+pub struct OverlappingModel {
+    n: u32,
+    /// `patterns[pattern][y * n + x]` is the pixel at (x, y) of the pattern
+    patterns: Vec<Vec<Rgba<u8>>>,
+    rules: RuleSet<Pattern>
+}
+
+/// How patterns are taken from the sample of an [OverlappingModel]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OverlappingOptions {
+    /// The sample wraps around its edges, so patterns are also taken from the squares that cross them
+    pub periodic_input: bool,
+    /// Patterns are also used rotated 90, 180 and 270 degrees
+    pub rotations: bool,
+    /// Patterns are also used mirrored (and their rotations mirrored, if [OverlappingOptions::rotations])
+    pub reflections: bool
+}
+
+/// A pattern of an [OverlappingModel], the tile of its boards.
+///
+/// Its rules come from the sample, so boards of patterns can only be made with [OverlappingModel::board()]
+/// ```compile_fail
+/// use procedural::Board;
+/// use procedural::overlapping::Pattern;
+/// let board = Board::<Pattern>::new(10, 10, 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pattern(usize);
+
+impl BoardTile for Pattern {
+    type Direction = Direction4;
+
+    #[cfg(feature = "view3d")]
+    fn get_name(&self) -> String {
+        format!("pattern{}", self.0)
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)> {
+        None
+    }
+
+    #[cfg(feature = "view3d")]
+    fn has_model(&self) -> bool {
+        false
+    }
+}
+
+impl OverlappingModel {
+    /// Takes every `n`x`n` pattern of `sample`. The more times a pattern is found, the more likely it is to be placed.
+    ///
+    /// # Panics
+    /// If `n` is 0 or the sample is smaller than `n`x`n`
+    pub fn new(sample: &RgbaImage, n: u32, options: OverlappingOptions) -> OverlappingModel {
+        let (width, height) = sample.dimensions();
+        assert!(n > 0 && width >= n && height >= n, "the sample is smaller than the patterns");
+        let (max_x, max_y) = match options.periodic_input {
+            true => (width, height),
+            false => (width - n + 1, height - n + 1),
+        };
+
+        let mut patterns = vec![];
+        let mut index = HashMap::new();
+        let mut weights = vec![];
+        for y in 0..max_y {
+            for x in 0..max_x {
+                let pattern = (0..n * n)
+                    .map(|i| *sample.get_pixel((x + i % n) % width, (y + i / n) % height))
+                    .collect::<Vec<_>>();
+                for variant in variants(pattern, n as usize, options) {
+                    let i = *index.entry(variant.clone()).or_insert_with(|| {
+                        patterns.push(variant);
+                        weights.push(0);
+                        patterns.len() - 1
+                    });
+                    weights[i] += 1;
+                }
+            }
+        }
+
+        let directions = Direction4::<false, false>::all();
+        let allowed = patterns.iter().map(|pattern| directions.iter().map(|direction| {
+            // How the pattern in `direction` is displaced, found by going there from the middle of a 3x3 board
+            let (row, col, _) = direction.neighbour(1, 1, 0, 3, 3, 1).unwrap();
+            let offset = (col as i64 - 1, row as i64 - 1);
+            patterns.iter()
+                .enumerate()
+                .filter(|(_, other)| overlap(pattern, other, n as i64, offset))
+                .map(|(i, _)| i)
+                .collect()
+        }).collect()).collect();
+
+        let tiles = (0..patterns.len()).map(Pattern).collect();
+        let possibles = (0..patterns.len()).collect::<TileSet>();
+        OverlappingModel {
+            n,
+            patterns,
+            rules: RuleSet {
+                rules: Rules::from_table(tiles, allowed, vec![(possibles, weights)])
+            }
+        }
+    }
+
+    /// How many different patterns were found in the sample
+    pub fn patterns(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// An empty board for an image of `width`x`height` pixels, to be generated with any of the methods of [Board]
+    /// and turned into an image with [OverlappingModel::image()]
+    ///
+    /// # Panics
+    /// If the image is smaller than the patterns
+    pub fn board(&self, width: u32, height: u32) -> Board<Pattern> {
+        assert!(width >= self.n && height >= self.n, "the image is smaller than the patterns");
+        Board::with_rule_set(width - self.n + 1, height - self.n + 1, 1, self.rules.clone())
+    }
+
+    /// The image of a board created with [OverlappingModel::board()].
+    /// The pixels that aren't decided yet are the average of all the colors they can still be
+    pub fn image(&self, board: &Board<Pattern>) -> RgbaImage {
+        let n = self.n as usize;
+        let (columns, rows) = (board.width as usize, board.length as usize);
+        RgbaImage::from_fn((columns + n - 1) as u32, (rows + n - 1) as u32, |x, y| {
+            let (x, y) = (x as usize, y as usize);
+            let (col, row) = (x.min(columns - 1), y.min(rows - 1));
+            let pixel = (y - row) * n + x - col;
+            match board.get_tile(row, col, 0).unwrap() {
+                MaybeTile::Decided(pattern) => self.patterns[pattern.0][pixel],
+                MaybeTile::Undecided(patterns) => {
+                    let mut sum = [0u32; 4];
+                    for pattern in patterns.iter() {
+                        for (channel, value) in self.patterns[pattern.0][pixel].0.iter().enumerate() {
+                            sum[channel] += *value as u32
+                        }
+                    }
+                    let count = (patterns.len() as u32).max(1);
+                    Rgba(sum.map(|channel| (channel / count) as u8))
+                }
+            }
+        })
+    }
+
+    /// Generates an image of `width`x`height` pixels. The same seed always generates the same image
+    ///
+    /// # Panics
+    /// If the image is smaller than the patterns
+    pub fn generate(&self, width: u32, height: u32, seed: u64) -> Result<RgbaImage, ImpossibleBoardError<Pattern>> {
+        let mut board = self.board(width, height);
+        board.reseed(seed);
+        board.generate()?;
+        Ok(self.image(&board))
+    }
+}
+
+/// The pattern and the rotations and reflections of it required by `options`
+fn variants(pattern: Vec<Rgba<u8>>, n: usize, options: OverlappingOptions) -> Vec<Vec<Rgba<u8>>> {
+    let transform = |pattern: &[Rgba<u8>], f: &dyn Fn(usize, usize) -> (usize, usize)| {
+        (0..n * n).map(|i| {
+            let (x, y) = f(i % n, i / n);
+            pattern[y * n + x]
+        }).collect::<Vec<_>>()
+    };
+    let mut variants = vec![pattern];
+    if options.rotations {
+        for _ in 0..3 {
+            let rotated = transform(variants.last().unwrap(), &|x, y| (y, n - 1 - x));
+            variants.push(rotated)
+        }
+    }
+    if options.reflections {
+        let reflected = variants.iter()
+            .map(|pattern| transform(pattern, &|x, y| (n - 1 - x, y)))
+            .collect::<Vec<_>>();
+        variants.extend(reflected)
+    }
+    variants
+}
+
+/// If `other`, displaced by `(x, y)` pixels, has the same pixels as `pattern` where they overlap
+fn overlap(pattern: &[Rgba<u8>], other: &[Rgba<u8>], n: i64, (dx, dy): (i64, i64)) -> bool {
+    (dy.max(0)..n.min(n + dy)).all(|y| (dx.max(0)..n.min(n + dx)).all(|x| {
+        pattern[(y * n + x) as usize] == other[((y - dy) * n + x - dx) as usize]
+    }))
+}
@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{BoardTile, Direction, ordered};
+use crate::rules::Rules;
+use crate::tile_set::TileSet;
+
+/// Rules for a board that don't come from the implementation of [Tile](crate::Tile), for example learned from a sample.
+///
+/// A board created with [Board::with_rule_set()](crate::Board::with_rule_set()) ignores [Tile::all()](crate::Tile::all()), [Tile::possibles()](crate::Tile::possibles()),
+/// [Tile::get_rules()](crate::Tile::get_rules()) and [Tile::get_distribution()](crate::Tile::get_distribution()), and uses the rule set instead.
+/// ```
+#[doc = include_str!("../doc_helpers/MyTile.rs")]
+/// use procedural::{Board, MaybeTile, RuleSet};
+/// let sample = vec![vec![
+///     vec![MyTile::Green, MyTile::Yellow, MyTile::Green],
+///     vec![MyTile::Yellow, MyTile::Green, MyTile::Yellow],
+/// ]];
+/// let rules = RuleSet::learn(&sample);
+/// assert_eq!(rules.weight(&MyTile::Green, 0), 3);
+/// assert_eq!(rules.weight(&MyTile::Red, 0), 0);
+/// let mut board = Board::with_rule_set(5, 5, 1, rules);
+/// board.generate().unwrap();
+/// // Red is not in the sample, so it's never placed
+/// for row in 0..5 {
+///     for col in 0..5 {
+///         assert_ne!(board.get_tile(row, col, 0).unwrap(), MaybeTile::Decided(MyTile::Red));
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct RuleSet<T>
+where
+    T: BoardTile
+{
+    pub(crate) rules: Rules<T>
+}
+
+impl<T> RuleSet<T>
+where
+    T: BoardTile
+{
+    /// Learns the rules from a sample board, indexed as `sample[layer][row][col]`.
+    ///
+    /// Two tiles can be next to each other in a direction only if they are next to each other in that direction somewhere in the sample,
+    /// as given by [Direction::neighbour()]. Only the tiles found in a layer of the sample can be in that layer, and
+    /// their weight is how many times they are found there. Layers of the board past the last layer of the sample are like the last one.
+    ///
+    /// # Panics
+    /// If the sample is empty or not every layer has the same amount of rows and every row the same amount of columns
+    pub fn learn(sample: &[Vec<Vec<T>>]) -> RuleSet<T> {
+        let height = sample.len();
+        let length = sample.first().map_or(0, |layer| layer.len());
+        let width = sample.first().and_then(|layer| layer.first()).map_or(0, |row| row.len());
+        assert!(width * length * height > 0, "the sample is empty");
+        assert!(
+            sample.iter().all(|layer| layer.len() == length && layer.iter().all(|row| row.len() == width)),
+            "the sample is not a box"
+        );
+
+        let tiles = ordered(sample.iter().flatten().flatten().collect::<HashSet<_>>().into_iter());
+        let index: HashMap<T, usize> = tiles.iter().enumerate().map(|(i, tile)| (tile.clone(), i)).collect();
+        let directions = T::Direction::all();
+        let mut allowed = vec![vec![TileSet::new(); directions.len()]; tiles.len()];
+        let mut layers = vec![(TileSet::new(), vec![0; tiles.len()]); height];
+        for (layer, rows) in sample.iter().enumerate() {
+            for (row, tiles) in rows.iter().enumerate() {
+                for (col, tile) in tiles.iter().enumerate() {
+                    let tile = index[tile];
+                    layers[layer].0.insert(tile);
+                    layers[layer].1[tile] += 1;
+                    for (d, direction) in directions.iter().enumerate() {
+                        if let Ok((row, col, layer)) = direction.neighbour(row, col, layer, width as u32, length as u32, height as u32) {
+                            allowed[tile][d].insert(index[&sample[layer][row][col]])
+                        }
+                    }
+                }
+            }
+        }
+
+        RuleSet {
+            rules: Rules::from_table(tiles, allowed, layers)
+        }
+    }
+
+    /// All the tiles that can be placed somewhere
+    pub fn tiles(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.rules.len()).map(|tile| self.rules.tile(tile))
+    }
+
+    /// If `other` can be in `direction` from `tile`. Like calling `tile.get_rules()(other, direction)`
+    pub fn allows(&self, tile: &T, other: &T, direction: T::Direction) -> bool
+    where
+        T::Direction: PartialEq
+    {
+        let direction = self.rules.directions().iter().position(|other| *other == direction);
+        match (self.rules.index(tile), self.rules.index(other), direction) {
+            (Some(tile), Some(other), Some(direction)) => self.rules.allows(tile, direction, other),
+            _ => false,
+        }
+    }
+
+    /// How likely `tile` is to be placed in `layer`, 0 if it can't be placed there. Like [Tile::get_distribution()](crate::Tile::get_distribution())
+    pub fn weight(&self, tile: &T, layer: usize) -> u32 {
+        match self.rules.index(tile) {
+            Some(tile) if self.rules.possibles(layer).contains(tile) => self.rules.weight(tile, layer),
+            _ => 0,
+        }
+    }
+}
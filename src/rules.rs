@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::{BoardTile, Direction, Fnv1a, Tile, ordered};
+use crate::tile_set::TileSet;
+
+/// Which tiles can be next to each other, precomputed once from [Tile::get_rules()]
+/// so propagating doesn't have to call it every time.
+///
+/// Every tile of [Tile::all()] gets a dense index, which is what the board stores instead of the tile itself.
+#[derive(Clone)]
+pub(crate) struct Rules<T>
+where
+    T: BoardTile
+{
+    tiles: Vec<T>,
+    index: HashMap<T, usize>,
+    directions: Vec<T::Direction>,
+    /// `allowed[tile][direction]` are the tiles that can be in `direction` from `tile`
+    allowed: Vec<Vec<TileSet>>,
+    layers: Layers<T>
+}
+
+/// Where the possibles and weights of every layer come from
+#[derive(Clone)]
+enum Layers<T> {
+    /// [Tile::possibles()] and [Tile::get_distribution()]
+    Tile(fn(usize) -> HashSet<T>, fn(&T, usize) -> u32),
+    /// The rules don't come from [Tile] (see [RuleSet](crate::RuleSet)), `table[layer]` are the possibles of `layer`
+    /// and the weight of every tile in it. Layers past the last one are like the last one
+    Table(Vec<(TileSet, Vec<u32>)>)
+}
+
+impl<T> Rules<T>
+where
+    T: Tile
+{
+    pub(crate) fn new() -> Rules<T> {
+        let tiles = ordered(T::all().iter());
+        let index = tiles.iter().enumerate().map(|(i, tile)| (*tile, i)).collect();
+        let directions = T::Direction::all();
+        let allowed = tiles.iter().map(|tile| {
+            let can_stay = tile.get_rules();
+            directions.iter().map(|direction| {
+                tiles.iter()
+                    .enumerate()
+                    .filter(|(_, other)| can_stay(other, *direction))
+                    .map(|(i, _)| i)
+                    .collect()
+            }).collect()
+        }).collect();
+
+        Rules {
+            tiles,
+            index,
+            directions,
+            allowed,
+            layers: Layers::Tile(T::possibles, T::get_distribution)
+        }
+    }
+}
+
+impl<T> Rules<T>
+where
+    T: BoardTile
+{
+    /// Rules that ignore [Tile::all()], [Tile::possibles()], [Tile::get_rules()] and [Tile::get_distribution()].
+    /// `allowed` and the weights of `layers` are indexed like `tiles`
+    pub(crate) fn from_table(tiles: Vec<T>, allowed: Vec<Vec<TileSet>>, layers: Vec<(TileSet, Vec<u32>)>) -> Rules<T> {
+        let index = tiles.iter().enumerate().map(|(i, tile)| (tile.clone(), i)).collect();
+        Rules {
+            tiles,
+            index,
+            directions: T::Direction::all(),
+            allowed,
+            layers: Layers::Table(layers)
+        }
+    }
+
+    /// A hash of the tiles in the order of their indices. Boards with the same fingerprint store the same tiles with the same indices
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        self.tiles.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// How many tiles there are
+    pub(crate) fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// All directions, in the order used to index the table
+    pub(crate) fn directions(&self) -> &[T::Direction] {
+        &self.directions
+    }
+
+    /// The tile that has the given index
+    pub(crate) fn tile(&self, index: usize) -> T {
+        self.tiles[index].clone()
+    }
+
+    /// The index of the given tile, or None if it's not in [Tile::all()]
+    pub(crate) fn index(&self, tile: &T) -> Option<usize> {
+        self.index.get(tile).copied()
+    }
+
+    /// Converts a set of tiles to a [TileSet]. None if any of them is not in [Tile::all()]
+    pub(crate) fn to_tile_set(&self, tiles: &HashSet<T>) -> Option<TileSet> {
+        tiles.iter().map(|tile| self.index(tile)).collect()
+    }
+
+    pub(crate) fn to_hash_set(&self, set: &TileSet) -> HashSet<T> {
+        set.iter().map(|i| self.tile(i)).collect()
+    }
+
+    /// The initial possibilities of a slot in `layer`. See [Tile::possibles()]
+    pub(crate) fn possibles(&self, layer: usize) -> TileSet {
+        match &self.layers {
+            Layers::Tile(possibles, _) => possibles(layer).iter().filter_map(|tile| self.index(tile)).collect(),
+            Layers::Table(table) => table_layer(table, layer).map_or_else(TileSet::new, |(possibles, _)| possibles.clone()),
+        }
+    }
+
+    /// How likely `tile` is to be placed in `layer`. See [Tile::get_distribution()]
+    pub(crate) fn weight(&self, tile: usize, layer: usize) -> u32 {
+        match &self.layers {
+            Layers::Tile(_, distribution) => distribution(&self.tile(tile), layer),
+            Layers::Table(table) => table_layer(table, layer).map_or(0, |(_, weights)| weights[tile]),
+        }
+    }
+
+    /// If `other` can be in the `direction`th direction from `tile`
+    pub(crate) fn allows(&self, tile: usize, direction: usize, other: usize) -> bool {
+        self.allowed[tile][direction].contains(other)
+    }
+
+    /// If at least one of `tiles` can be in the `direction`th direction from `tile`
+    pub(crate) fn allows_any(&self, tile: usize, direction: usize, tiles: &TileSet) -> bool {
+        self.allowed[tile][direction].intersects(tiles)
+    }
+
+    /// All the tiles that can be in the `direction`th direction from at least one of `possibilities`
+    pub(crate) fn support(&self, possibilities: &TileSet, direction: usize) -> TileSet {
+        let mut support = TileSet::new();
+        for tile in possibilities.iter() {
+            support.union_with(&self.allowed[tile][direction])
+        }
+        support
+    }
+}
+
+/// The possibles and weights of `layer` in a table of [Layers::Table]
+fn table_layer(table: &[(TileSet, Vec<u32>)], layer: usize) -> Option<&(TileSet, Vec<u32>)> {
+    table.get(layer).or_else(|| table.last())
+}
@@ -0,0 +1,195 @@
+//! Ways of choosing which slot to decide next and which tile to place in it.
+//!
+//! Closures can be used as selectors too. See [CellSelector] and [TileSelector].
+
+use std::f64::consts::PI;
+
+use crate::{Board, BoardTile};
+
+/// Chooses which slot of the board will be decided next.
+///
+/// Every undecided slot of the current layer gets a score, and one of the slots with the lowest score is decided.
+/// Ties are broken randomly.
+///
+/// The score of a slot is only computed again when its possibilities change, so it shouldn't depend on other slots.
+///
+/// Any `Fn(&Board<T>, usize, usize, usize) -> f64` (taking the board, row, col and layer) is also a [CellSelector]
+/// ```
+#[doc = include_str!("../doc_helpers/MyTile.rs")]
+/// let mut board = procedural::Board::<MyTile>::new(5, 5, 1);
+/// // Decide the slots from right to left
+/// board.set_cell_selector(|_board: &procedural::Board<MyTile>, _row: usize, col: usize, _layer: usize| -(col as f64));
+/// board.generate().unwrap();
+/// ```
+pub trait CellSelector<T>
+where
+    T: BoardTile
+{
+    /// The score of the undecided slot at row, col, layer. Lower scores are decided first.
+    /// It's computed again every time the possibilities of the slot change.
+    fn score(&self, board: &Board<T>, row: usize, col: usize, layer: usize) -> f64;
+}
+
+impl<T, F> CellSelector<T> for F
+where
+    T: BoardTile,
+    F: Fn(&Board<T>, usize, usize, usize) -> f64
+{
+    fn score(&self, board: &Board<T>, row: usize, col: usize, layer: usize) -> f64 {
+        self(board, row, col, layer)
+    }
+}
+
+/// Chooses which tile will be placed in the slot that is being decided.
+///
+/// Every possible tile gets a weight, and one of them is chosen randomly, where a tile with weight 2 is twice as likely as one with weight 1.
+/// If all of them have weight 0, they are all equally likely.
+///
+/// Any `Fn(&Board<T>, T, usize, usize, usize) -> u32` (taking the board, tile, row, col and layer) is also a [TileSelector]
+/// ```
+#[doc = include_str!("../doc_helpers/MyTile.rs")]
+/// let mut board = procedural::Board::<MyTile>::new(5, 5, 1);
+/// // Place red tiles whenever possible
+/// board.set_tile_selector(|_board: &procedural::Board<MyTile>, tile: MyTile, _row: usize, _col: usize, _layer: usize| match tile {
+///     MyTile::Red => 1,
+///     _ => 0
+/// });
+/// board.generate().unwrap();
+/// assert_eq!(board.get_tile(0, 0, 0).unwrap(), procedural::MaybeTile::Decided(MyTile::Red));
+/// ```
+pub trait TileSelector<T>
+where
+    T: BoardTile
+{
+    /// How likely `tile` is to be placed at row, col, layer
+    fn weight(&self, board: &Board<T>, tile: T, row: usize, col: usize, layer: usize) -> u32;
+}
+
+impl<T, F> TileSelector<T> for F
+where
+    T: BoardTile,
+    F: Fn(&Board<T>, T, usize, usize, usize) -> u32
+{
+    fn weight(&self, board: &Board<T>, tile: T, row: usize, col: usize, layer: usize) -> u32 {
+        self(board, tile, row, col, layer)
+    }
+}
+
+/// Decides first the slot with the fewest possibilities. This is the default [CellSelector]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinPossibilities;
+
+impl<T> CellSelector<T> for MinPossibilities
+where
+    T: BoardTile
+{
+    fn score(&self, board: &Board<T>, row: usize, col: usize, layer: usize) -> f64 {
+        board.tiles[layer][row][col].len() as f64
+    }
+}
+
+/// Decides first the slot with the lowest Shannon entropy, weighting each possibility with [Tile::get_distribution()](crate::Tile::get_distribution())
+/// (or the weights of the [RuleSet](crate::RuleSet) of the board).
+///
+/// This is what wave function collapse usually does, and gives better results when some tiles are much rarer than others.
+/// If all the possibilities of a slot have weight 0, they count as equally likely.
+/// ```
+/// # use std::collections::HashSet;
+/// # use procedural::directions::Direction4;
+/// # #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// # enum Weightless { A, B }
+/// # impl procedural::Tile for Weightless {
+/// #     type Direction = Direction4;
+/// #     fn all() -> HashSet<Self> { HashSet::from([Weightless::A, Weightless::B]) }
+/// #     fn possibles(_layer: usize) -> HashSet<Self> { Self::all() }
+/// #     fn get_rules(&self) -> Box<dyn Fn(&Self, Direction4) -> bool + '_> { Box::new(|_: &Self, _: Direction4| true) }
+/// #     fn get_distribution(&self, _layer: usize) -> u32 { 0 }
+/// # }
+/// use procedural::selection::{Distribution, Entropy};
+/// // Every tile of Weightless has a distribution of 0
+/// let mut board = procedural::Board::<Weightless>::with_selectors(5, 5, 1, Entropy, Distribution);
+/// board.generate().unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Entropy;
+
+impl<T> CellSelector<T> for Entropy
+where
+    T: BoardTile
+{
+    fn score(&self, board: &Board<T>, row: usize, col: usize, layer: usize) -> f64 {
+        let slot = &board.tiles[layer][row][col];
+        let (sum, sum_log) = slot.iter()
+            .map(|tile| board.rules.weight(tile, layer) as f64)
+            .filter(|weight| *weight > 0.0)
+            .fold((0.0, 0.0), |(sum, sum_log), weight| (sum + weight, sum_log + weight * weight.ln()));
+        if sum == 0.0 {
+            // No weights, so every possibility is equally likely
+            return (slot.len().max(1) as f64).ln()
+        }
+        sum.ln() - sum_log / sum
+    }
+}
+
+/// Decides the slots row by row, from left to right
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Scanline;
+
+impl<T> CellSelector<T> for Scanline
+where
+    T: BoardTile
+{
+    fn score(&self, board: &Board<T>, row: usize, col: usize, _layer: usize) -> f64 {
+        (row * board.width as usize + col) as f64
+    }
+}
+
+/// Decides the slots in a spiral, starting from a point, so the board grows from there
+#[derive(Debug, Clone, Copy)]
+pub struct Spiral {
+    /// Row of the first slot to decide
+    pub row: usize,
+    /// Column of the first slot to decide
+    pub col: usize
+}
+
+impl<T> CellSelector<T> for Spiral
+where
+    T: BoardTile
+{
+    fn score(&self, _board: &Board<T>, row: usize, col: usize, _layer: usize) -> f64 {
+        let y = row as f64 - self.row as f64;
+        let x = col as f64 - self.col as f64;
+        let ring = x.abs().max(y.abs());
+        // Goes from 0 to 1 around the ring, so the ring is finished before going to the next one
+        let turn = (y.atan2(x) + PI) / (2.0 * PI + f64::EPSILON);
+        ring + turn
+    }
+}
+
+/// Chooses tiles according to [Tile::get_distribution()](crate::Tile::get_distribution()), or the weights of the [RuleSet](crate::RuleSet) of the board.
+/// This is the default [TileSelector]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Distribution;
+
+impl<T> TileSelector<T> for Distribution
+where
+    T: BoardTile
+{
+    fn weight(&self, board: &Board<T>, tile: T, _row: usize, _col: usize, layer: usize) -> u32 {
+        board.rules.index(&tile).map_or(0, |tile| board.rules.weight(tile, layer))
+    }
+}
+
+/// All possible tiles are equally likely
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+impl<T> TileSelector<T> for Uniform
+where
+    T: BoardTile
+{
+    fn weight(&self, _board: &Board<T>, _tile: T, _row: usize, _col: usize, _layer: usize) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,120 @@
+//! Rules made of labelled sides that have to match, instead of closures. See [SocketTile]
+
+use std::collections::HashSet;
+#[cfg(feature = "validate")]
+use std::fmt::Debug;
+use std::hash::Hash;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+#[cfg(feature = "view3d")]
+use te_renderer::model::ModelVertex;
+
+use crate::{Direction, Tile};
+
+macro_rules! socket_tile {
+    () => {
+        /// Directions that are relevant to this type of tile.
+        type Direction: Direction;
+        /// The label of a side of a tile
+        type Socket: PartialEq;
+
+        /// Set containing all the possible tiles.
+        fn all() -> HashSet<Self>;
+
+        /// Like [SocketTile::all()], but depending on the layer.
+        fn possibles(layer: usize) -> HashSet<Self>;
+        #[cfg(feature = "view3d")]
+        /// **distinct** name, one for each tile with a different model.
+        fn get_name(&self) -> String;
+        #[cfg(feature = "view3d")]
+        /// If the model has a model, returns its vertex and triangle indices
+        fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)>;
+        #[cfg(feature = "view3d")]
+        /// If this tile has a model or not (is invisible)
+        fn has_model(&self) -> bool;
+        /// The label of the side of this tile that faces `direction`
+        fn socket(&self, direction: Self::Direction) -> Self::Socket;
+        /// If a side labelled `socket` can touch a side labelled `other`. By default, only sides with the same label can.
+        ///
+        /// Two sides can touch if this is true in any order, so every pair of labels only has to be written once
+        fn compatible(socket: &Self::Socket, other: &Self::Socket) -> bool {
+            socket == other
+        }
+        /// Get the chance of this tile being chosen randomly. See [Tile::get_distribution()]
+        fn get_distribution(&self, layer: usize) -> u32;
+    }
+}
+
+#[cfg(feature = "validate")]
+/// A tile whose rules come from a label on each of its sides, called socket.
+///
+/// The board uses [Socketed] tiles, where two tiles can be next to each other
+/// if the sockets of the sides that touch are compatible, so the rules are always bidirectional.
+/// ```
+#[doc = include_str!("../doc_helpers/RoadTile.rs")]
+/// ```
+pub trait SocketTile: Sized + Eq + PartialEq + Hash + Clone + Copy + Debug {
+    socket_tile!();
+}
+
+#[cfg(not(feature = "validate"))]
+/// A tile whose rules come from a label on each of its sides, called socket.
+///
+/// The board uses [Socketed] tiles, where two tiles can be next to each other
+/// if the sockets of the sides that touch are compatible, so the rules are always bidirectional.
+/// ```
+#[doc = include_str!("../doc_helpers/RoadTile.rs")]
+/// ```
+pub trait SocketTile: Sized + Eq + PartialEq + Hash + Clone + Copy {
+    socket_tile!();
+}
+
+/// A [SocketTile] placed in a board. This is the [Tile] of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Socketed<S>(pub S)
+where
+    S: SocketTile;
+
+impl<S> Tile for Socketed<S>
+where
+    S: SocketTile
+{
+    type Direction = S::Direction;
+
+    fn all() -> HashSet<Self> {
+        S::all().into_iter().map(Socketed).collect()
+    }
+
+    fn possibles(layer: usize) -> HashSet<Self> {
+        S::possibles(layer).into_iter().map(Socketed).collect()
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_name(&self) -> String {
+        self.0.get_name()
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)> {
+        self.0.get_model()
+    }
+
+    #[cfg(feature = "view3d")]
+    fn has_model(&self) -> bool {
+        self.0.has_model()
+    }
+
+    fn get_rules(&self) -> Box<dyn Fn(&Self, Self::Direction) -> bool + '_> {
+        Box::new(move |other: &Self, direction: Self::Direction| {
+            let socket = self.0.socket(direction);
+            let other = other.0.socket(direction.opposite());
+            S::compatible(&socket, &other) || S::compatible(&other, &socket)
+        })
+    }
+
+    fn get_distribution(&self, layer: usize) -> u32 {
+        self.0.get_distribution(layer)
+    }
+}
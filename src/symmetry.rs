@@ -0,0 +1,352 @@
+//! Tiles that can be rotated and reflected, so only one orientation of them has to be written. See [SymmetricTile]
+//!
+//! A set of pipes, where every pipe has to continue in the next tile:
+//! ```
+//! use std::collections::HashSet;
+//! use procedural::{Board, Direction, MaybeTile, Tile};
+//! use procedural::directions::Direction4;
+//! use procedural::symmetry::{Oriented, SymmetricTile, Symmetry};
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//! enum Pipe {
+//!     Empty,
+//!     Straight,
+//!     Corner,
+//!     Tee
+//! }
+//!
+//! impl Pipe {
+//!     /// If the pipe reaches `side` of the tile, when it's not rotated
+//!     fn connects(&self, side: Direction4) -> bool {
+//!         match self {
+//!             Pipe::Empty => false,
+//!             Pipe::Straight => matches!(side, Direction4::North | Direction4::South),
+//!             Pipe::Corner => matches!(side, Direction4::North | Direction4::East),
+//!             Pipe::Tee => side != Direction4::North,
+//!         }
+//!     }
+//! }
+//!
+//! impl SymmetricTile for Pipe {
+//!     type Direction = Direction4;
+//!
+//!     fn all() -> HashSet<Self> {
+//!         HashSet::from([Pipe::Empty, Pipe::Straight, Pipe::Corner, Pipe::Tee])
+//!     }
+//!
+//!     fn possibles(_layer: usize) -> HashSet<Self> {
+//!         Self::all()
+//!     }
+//!
+//!     fn symmetry(&self) -> Symmetry {
+//!         match self {
+//!             Pipe::Empty => Symmetry::X,
+//!             Pipe::Straight => Symmetry::I,
+//!             Pipe::Corner => Symmetry::L,
+//!             Pipe::Tee => Symmetry::T,
+//!         }
+//!     }
+//!
+//!     fn get_rules(&self) -> Box<dyn Fn(&Oriented<Pipe>, Direction4) -> bool + '_> {
+//!         // Either both tiles have a pipe where they touch, or none of them
+//!         Box::new(move |other: &Oriented<Pipe>, direction: Direction4| {
+//!             self.connects(direction) == other.tile().connects(other.base_direction(direction.opposite()))
+//!         })
+//!     }
+//!
+//!     fn get_distribution(&self, _layer: usize) -> u32 {
+//!         1
+//!     }
+//! }
+//!
+//! // 1 empty tile, 2 straight pipes, 4 corners and 4 tees
+//! assert_eq!(Oriented::<Pipe>::all().len(), 11);
+//! assert!(procedural::validate::<Oriented<Pipe>>().is_ok());
+//! let mut board = Board::<Oriented<Pipe>>::new(8, 8, 1);
+//! board.generate().unwrap();
+//! let connects = |row, col, direction| match board.get_tile(row, col, 0).unwrap() {
+//!     MaybeTile::Decided(pipe) => pipe.tile().connects(pipe.base_direction(direction)),
+//!     MaybeTile::Undecided(_) => unreachable!(),
+//! };
+//! for row in 0..8 {
+//!     for col in 0..7 {
+//!         assert_eq!(connects(row, col, Direction4::East), connects(row, col + 1, Direction4::West));
+//!     }
+//! }
+//! ```
+
+use std::collections::HashSet;
+#[cfg(feature = "validate")]
+use std::fmt::Debug;
+use std::hash::Hash;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+#[cfg(feature = "view3d")]
+use te_renderer::model::ModelVertex;
+
+use crate::{Direction, Tile};
+
+/// Directions of boards whose tiles can be rotated a quarter turn and mirrored. See [SymmetricTile]
+pub trait Rotate: Direction {
+    /// The direction after a clockwise quarter turn, seen from above. North becomes east
+    fn rotate(&self) -> Self;
+    /// The direction after mirroring east and west
+    fn reflect(&self) -> Self;
+}
+
+/// How a tile looks the same after being rotated and reflected, which decides how many different versions of it there are.
+/// Named after the shape of the letters they look like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Symmetry {
+    /// Looks the same in every orientation, like grass. 1 version
+    X,
+    /// A line from north to south, looks the same mirrored and turned around. 2 versions
+    I,
+    /// A line from the north-west corner to the south-east corner, like `\`. 2 versions
+    Diagonal,
+    /// Looks the same mirrored east-west, like a T. 4 versions
+    T,
+    /// A corner joining north and east, looks the same mirrored across the diagonal between them, like an L. 4 versions
+    L,
+    /// Every rotation and reflection is different. 8 versions
+    D4
+}
+
+impl Symmetry {
+    /// The orientations that leave the tile as it was
+    fn stabilizer(&self) -> Vec<Orientation> {
+        let orientations: &[(bool, u8)] = match self {
+            Symmetry::X => &[(false, 0), (false, 1), (false, 2), (false, 3), (true, 0), (true, 1), (true, 2), (true, 3)],
+            Symmetry::I => &[(false, 0), (false, 2), (true, 0), (true, 2)],
+            Symmetry::Diagonal => &[(false, 0), (false, 2), (true, 1), (true, 3)],
+            Symmetry::T => &[(false, 0), (true, 0)],
+            Symmetry::L => &[(false, 0), (true, 1)],
+            Symmetry::D4 => &[(false, 0)],
+        };
+        orientations.iter()
+            .map(|(reflected, rotation)| Orientation { reflected: *reflected, rotation: *rotation })
+            .collect()
+    }
+}
+
+/// A way of placing a tile: first mirrored east-west if `reflected`, then rotated `rotation` clockwise quarter turns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Orientation {
+    /// If the tile is mirrored east-west before rotating it
+    pub reflected: bool,
+    /// Clockwise quarter turns, from 0 to 3
+    pub rotation: u8
+}
+
+impl Orientation {
+    /// All 8 orientations
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        [false, true].into_iter()
+            .flat_map(|reflected| (0..4).map(move |rotation| Orientation { reflected, rotation }))
+    }
+
+    /// Where `direction` ends up after placing something with this orientation
+    pub fn apply<D>(&self, direction: D) -> D
+    where
+        D: Rotate
+    {
+        let mut direction = match self.reflected {
+            true => direction.reflect(),
+            false => direction,
+        };
+        for _ in 0..self.rotation % 4 {
+            direction = direction.rotate()
+        }
+        direction
+    }
+
+    /// The orientation that undoes this one
+    pub fn inverse(&self) -> Orientation {
+        match self.reflected {
+            // Reflecting and then rotating is the same as rotating the other way and then reflecting
+            true => *self,
+            false => Orientation { reflected: false, rotation: (4 - self.rotation % 4) % 4 },
+        }
+    }
+
+    /// The orientation of applying `first` and then this one
+    pub fn after(&self, first: Orientation) -> Orientation {
+        let rotation = match self.reflected {
+            true => self.rotation + 4 - first.rotation % 4,
+            false => self.rotation + first.rotation,
+        };
+        Orientation {
+            reflected: self.reflected != first.reflected,
+            rotation: rotation % 4
+        }
+    }
+}
+
+macro_rules! symmetric_tile {
+    () => {
+        /// Directions that are relevant to this type of tile.
+        type Direction: Rotate;
+
+        /// Set containing all the possible tiles, without their orientations.
+        fn all() -> HashSet<Self>;
+
+        /// Like [SymmetricTile::all()], but depending on the layer.
+        fn possibles(layer: usize) -> HashSet<Self>;
+        /// Which orientations of the tile look the same
+        fn symmetry(&self) -> Symmetry;
+        #[cfg(feature = "view3d")]
+        /// **distinct** name, one for each tile with a different model. The orientation is added to it
+        fn get_name(&self) -> String;
+        #[cfg(feature = "view3d")]
+        /// If the model has a model, returns its vertex and triangle indices, without rotating it
+        fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)>;
+        #[cfg(feature = "view3d")]
+        /// If this tile has a model or not (is invisible)
+        fn has_model(&self) -> bool;
+        /// Determines which tiles can be next to this one when it's not rotated nor reflected, depending on the direction.
+        /// The rules of the other orientations are rotated and reflected from these
+        fn get_rules(&self) -> Box<dyn Fn(&Oriented<Self>, Self::Direction) -> bool + '_>;
+        /// Get the chance of every version of this tile being chosen randomly. See [Tile::get_distribution()]
+        fn get_distribution(&self, layer: usize) -> u32;
+    }
+}
+
+#[cfg(feature = "validate")]
+/// A tile whose rotations and reflections are generated automatically. See the [module documentation](self) for an example.
+///
+/// Only the rules of the tile as it is (not rotated nor reflected) have to be written, and the board uses [Oriented] tiles,
+/// with every different version of every tile and the rules rotated for each of them.
+/// Use [validate()](crate::validate()) on `Oriented<MyTile>` to check that the rules are bidirectional:
+/// the rotated rules that aren't are in [ValidationReport::one_way_rules](crate::ValidationReport::one_way_rules).
+pub trait SymmetricTile: Sized + Eq + PartialEq + Hash + Clone + Copy + Debug {
+    symmetric_tile!();
+}
+
+#[cfg(not(feature = "validate"))]
+/// A tile whose rotations and reflections are generated automatically. See the [module documentation](self) for an example.
+///
+/// Only the rules of the tile as it is (not rotated nor reflected) have to be written, and the board uses [Oriented] tiles,
+/// with every different version of every tile and the rules rotated for each of them.
+/// Use [validate()](crate::validate()) on `Oriented<MyTile>` to check that the rules are bidirectional:
+/// the rotated rules that aren't are in [ValidationReport::one_way_rules](crate::ValidationReport::one_way_rules).
+pub trait SymmetricTile: Sized + Eq + PartialEq + Hash + Clone + Copy {
+    symmetric_tile!();
+}
+
+/// A [SymmetricTile] placed with an [Orientation]. This is the [Tile] of the board.
+///
+/// Orientations that look the same are the same tile, so the orientation is always the first one
+/// (in the order of [Orientation]) that looks like the one it was created with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Oriented<B>
+where
+    B: SymmetricTile
+{
+    tile: B,
+    orientation: Orientation
+}
+
+impl<B> Oriented<B>
+where
+    B: SymmetricTile
+{
+    /// `tile` placed with `orientation`
+    pub fn new(tile: B, orientation: Orientation) -> Oriented<B> {
+        let orientation = tile.symmetry().stabilizer().into_iter()
+            .map(|same| orientation.after(same))
+            .min()
+            .unwrap_or(orientation);
+        Oriented {
+            tile,
+            orientation
+        }
+    }
+
+    /// The tile, as it is before orienting it
+    pub fn tile(&self) -> B {
+        self.tile
+    }
+
+    /// How the tile is placed
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// The side of the tile that ends up facing `direction` once it's placed
+    pub fn base_direction(&self, direction: B::Direction) -> B::Direction {
+        self.orientation.inverse().apply(direction)
+    }
+
+    /// Every different version of `tile`
+    fn variants(tile: B) -> impl Iterator<Item = Oriented<B>> {
+        Orientation::all().map(move |orientation| Oriented::new(tile, orientation))
+    }
+}
+
+impl<B> Tile for Oriented<B>
+where
+    B: SymmetricTile
+{
+    type Direction = B::Direction;
+
+    fn all() -> HashSet<Self> {
+        B::all().into_iter().flat_map(Oriented::variants).collect()
+    }
+
+    fn possibles(layer: usize) -> HashSet<Self> {
+        B::possibles(layer).into_iter().flat_map(Oriented::variants).collect()
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_name(&self) -> String {
+        match self.orientation {
+            Orientation { reflected: false, rotation: 0 } => self.tile.get_name(),
+            Orientation { reflected, rotation } => format!("{}_r{}{}", self.tile.get_name(), rotation, if reflected { "m" } else { "" }),
+        }
+    }
+
+    #[cfg(feature = "view3d")]
+    fn get_model(&self) -> Option<(Vec<ModelVertex>, Vec<u32>, String)> {
+        let (vertices, mut indices, texture) = self.tile.get_model()?;
+        let vertices = vertices.into_iter().map(|vertex| {
+            let [mut x, y, mut z] = vertex.position;
+            if self.orientation.reflected {
+                x = 1.0 - x
+            }
+            for _ in 0..self.orientation.rotation {
+                // Clockwise seen from above, north (z = 0) becomes east (x = 1)
+                (x, z) = (1.0 - z, x)
+            }
+            ModelVertex { position: [x, y, z], tex_coords: vertex.tex_coords }
+        }).collect();
+        if self.orientation.reflected {
+            // Mirroring turns the triangles inside out
+            for triangle in indices.chunks_mut(3) {
+                triangle.reverse()
+            }
+        }
+        Some((vertices, indices, texture))
+    }
+
+    #[cfg(feature = "view3d")]
+    fn has_model(&self) -> bool {
+        self.tile.has_model()
+    }
+
+    fn get_rules(&self) -> Box<dyn Fn(&Self, Self::Direction) -> bool + '_> {
+        let rules = self.tile.get_rules();
+        // Turn everything back so this tile is not oriented, and ask its rules
+        let inverse = self.orientation.inverse();
+        Box::new(move |other: &Self, direction: Self::Direction| {
+            rules(&Oriented::new(other.tile, inverse.after(other.orientation)), inverse.apply(direction))
+        })
+    }
+
+    fn get_distribution(&self, layer: usize) -> u32 {
+        self.tile.get_distribution(layer)
+    }
+}
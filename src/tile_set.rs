@@ -0,0 +1,127 @@
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+use smallvec::SmallVec;
+
+const BITS: usize = u64::BITS as usize;
+
+/// Set of tiles, where each tile is represented by its index in [Rules](crate::rules::Rules).
+///
+/// Sets with up to 128 tiles don't allocate, and every slot of the board has one.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct TileSet {
+    /// Removing tiles can leave zeros at the end, which don't change which tiles are in the set
+    words: SmallVec<[u64; 2]>
+}
+
+impl TileSet {
+    /// A set without any tile
+    pub(crate) fn new() -> TileSet {
+        TileSet { words: SmallVec::new() }
+    }
+
+    /// The words up to the last one that has any tile
+    fn significant_words(&self) -> &[u64] {
+        let len = self.words.iter().rposition(|word| *word != 0).map_or(0, |last| last + 1);
+        &self.words[..len]
+    }
+
+    pub(crate) fn insert(&mut self, tile: usize) {
+        let word = tile / BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0)
+        }
+        self.words[word] |= 1 << (tile % BITS)
+    }
+
+    pub(crate) fn remove(&mut self, tile: usize) {
+        if let Some(word) = self.words.get_mut(tile / BITS) {
+            *word &= !(1 << (tile % BITS))
+        }
+    }
+
+    pub(crate) fn contains(&self, tile: usize) -> bool {
+        match self.words.get(tile / BITS) {
+            Some(word) => word & (1 << (tile % BITS)) != 0,
+            None => false,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Keeps only the tiles that are also in `other`
+    pub(crate) fn intersect_with(&mut self, other: &TileSet) {
+        for (i, word) in self.words.iter_mut().enumerate() {
+            *word &= other.words.get(i).copied().unwrap_or(0)
+        }
+    }
+
+    /// If at least one tile is in both sets
+    pub(crate) fn intersects(&self, other: &TileSet) -> bool {
+        self.words.iter().zip(other.words.iter()).any(|(word, other)| word & other != 0)
+    }
+
+    /// Adds all the tiles of `other`
+    pub(crate) fn union_with(&mut self, other: &TileSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0)
+        }
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other
+        }
+    }
+
+    pub(crate) fn is_superset(&self, other: &TileSet) -> bool {
+        other.words.iter().enumerate().all(|(i, word)| {
+            word & !self.words.get(i).copied().unwrap_or(0) == 0
+        })
+    }
+
+    /// Indices of the tiles in this set, in ascending order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None
+                }
+                let bit = word.trailing_zeros() as usize;
+                // Clears the lowest bit
+                word &= word - 1;
+                Some(i * BITS + bit)
+            })
+        })
+    }
+}
+
+impl PartialEq for TileSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant_words() == other.significant_words()
+    }
+}
+
+impl Eq for TileSet {}
+
+impl Hash for TileSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant_words().hash(state)
+    }
+}
+
+impl FromIterator<usize> for TileSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> TileSet {
+        let mut set = TileSet::new();
+        for tile in iter {
+            set.insert(tile)
+        }
+        set
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(feature = "view3d")]
+use std::collections::HashSet;
+
+use crate::{Direction, Tile, ordered};
+
+/// Size of the board used by [validate()] to try out [Direction::neighbour()]
+const PROBE_SIZE: u32 = 4;
+
+/// Mistakes found in the implementation of a [Tile] and its [Direction]. See [validate()]
+pub struct ValidationReport<T>
+where
+    T: Tile
+{
+    /// `(tile, other, direction)`: `tile` allows `other` in `direction`, but `other` doesn't allow `tile` in the opposite direction
+    pub one_way_rules: Vec<(T, T, T::Direction)>,
+    #[cfg(feature = "view3d")]
+    /// Names returned by [Tile::get_name()] for more than one tile
+    pub duplicate_names: Vec<String>,
+    /// Directions whose `opposite().opposite()` goes somewhere else than themselves
+    pub bad_opposites: Vec<T::Direction>,
+    /// `(direction, (row, col, layer))`: going in `direction` from that position and then in the opposite direction doesn't come back
+    pub bad_neighbours: Vec<(T::Direction, (usize, usize, usize))>,
+    /// `(layer, tile)`: `tile` is in [Tile::possibles()] of `layer`, but not in [Tile::all()]
+    pub unknown_possibles: Vec<(usize, T)>,
+    /// `(layer, tile)`: `tile` can be in `layer`, but its [Tile::get_distribution()] is 0
+    pub zero_weights: Vec<(usize, T)>
+}
+
+impl<T> ValidationReport<T>
+where
+    T: Tile
+{
+    /// If no mistakes were found
+    pub fn is_ok(&self) -> bool {
+        #[cfg(feature = "view3d")]
+        if !self.duplicate_names.is_empty() {
+            return false
+        }
+        self.one_way_rules.is_empty()
+            && self.bad_opposites.is_empty()
+            && self.bad_neighbours.is_empty()
+            && self.unknown_possibles.is_empty()
+            && self.zero_weights.is_empty()
+    }
+}
+
+/// Checks that the rules of `T` are bidirectional, that its directions are the inverse of their opposites and that
+/// [Tile::possibles()] and [Tile::get_distribution()] make sense, as long as the board is at most 4x4x4.
+/// For bigger boards see [validate_with_size()]
+///
+/// The cost is O(n²*m) where n is the amount of tiles and m the amount of directions, so it's better suited for tests
+/// than for running every time a board is created.
+/// ```
+#[doc = include_str!("../doc_helpers/MyTile.rs")]
+/// let report = procedural::validate::<MyTile>();
+/// assert!(report.is_ok());
+/// ```
+pub fn validate<T>() -> ValidationReport<T>
+where
+    T: Tile
+{
+    validate_with_size(PROBE_SIZE, PROBE_SIZE, PROBE_SIZE)
+}
+
+/// Like [validate()], but checks the directions and layers of a board with the given size
+pub fn validate_with_size<T>(width: u32, length: u32, height: u32) -> ValidationReport<T>
+where
+    T: Tile
+{
+    let all = T::all();
+    let tiles = ordered(all.iter());
+    let directions = T::Direction::all();
+
+    let one_way_rules = tiles.iter()
+        .flat_map(|tile| tiles.iter().map(move |other| (*tile, *other)))
+        .flat_map(|(tile, other)| directions.iter().map(move |direction| (tile, other, *direction)))
+        .filter(|(tile, other, direction)| {
+            tile.get_rules()(other, *direction) && !other.get_rules()(tile, direction.opposite())
+        })
+        .collect();
+
+    #[cfg(feature = "view3d")]
+    let duplicate_names = {
+        let mut names = HashSet::new();
+        let mut duplicates = vec![];
+        for tile in tiles.iter() {
+            let name = tile.get_name();
+            if !names.insert(name.clone()) && !duplicates.contains(&name) {
+                duplicates.push(name)
+            }
+        }
+        duplicates
+    };
+
+    let positions = (0..height as usize)
+        .flat_map(|layer| (0..length as usize).map(move |row| (row, layer)))
+        .flat_map(|(row, layer)| (0..width as usize).map(move |col| (row, col, layer)))
+        .collect::<Vec<_>>();
+    let neighbour = |direction: &T::Direction, (row, col, layer): (usize, usize, usize)| {
+        direction.neighbour(row, col, layer, width, length, height).ok()
+    };
+
+    let bad_opposites = directions.iter()
+        .filter(|direction| positions.iter().any(|position| {
+            neighbour(&direction.opposite().opposite(), *position) != neighbour(direction, *position)
+        }))
+        .copied()
+        .collect();
+
+    let bad_neighbours = directions.iter()
+        .filter_map(|direction| positions.iter().find(|position| match neighbour(direction, **position) {
+            Some(next) => neighbour(&direction.opposite(), next) != Some(**position),
+            None => false,
+        }).map(|position| (*direction, *position)))
+        .collect();
+
+    let mut unknown_possibles = vec![];
+    let mut zero_weights = vec![];
+    for layer in 0..height as usize {
+        let possibles = T::possibles(layer);
+        for tile in ordered(possibles.iter()) {
+            if !all.contains(&tile) {
+                unknown_possibles.push((layer, tile))
+            } else if tile.get_distribution(layer) == 0 {
+                zero_weights.push((layer, tile))
+            }
+        }
+    }
+
+    ValidationReport {
+        one_way_rules,
+        #[cfg(feature = "view3d")]
+        duplicate_names,
+        bad_opposites,
+        bad_neighbours,
+        unknown_possibles,
+        zero_weights
+    }
+}